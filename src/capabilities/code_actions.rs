@@ -0,0 +1,641 @@
+use std::collections::HashSet;
+
+use auto_lsp::{
+    anyhow,
+    core::{ast::AstNode, document::Document},
+    default::db::{
+        BaseDatabase,
+        tracked::{ParsedAst, get_ast},
+    },
+    lsp_types::{
+        CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionResponse,
+        Command, Diagnostic, Position, Range, RenameParams, TextDocumentIdentifier,
+        TextDocumentPositionParams, TextEdit, Url, WorkspaceEdit,
+    },
+};
+
+use crate::{
+    ast::{Error, Method, Struct, Typedef, Typeref},
+    capabilities::{
+        complexity::extract_complex,
+        dangling_docs::attach_doc,
+        diagnostics::{
+            _diagnostics, INVALID_NAME, TRAILING_NEWLINE, UNKNOWN_TYPE, UNUSED_TYPE, WRONG_KIND,
+            join_names, reachable_types,
+        },
+        documentation::insert_doc,
+        duplicates::extract_duplicates,
+        extract_type::{extract_type, unique_type_name},
+        hover::{fix_indent, get_doc_lines},
+        infer_type::infer_type,
+        inline_type::inline_type,
+        loose_types::infer_from_sample,
+        naming,
+        organize::organize_declarations,
+        rename::rename,
+        suppress::suppress,
+        type_modifiers::type_modifiers,
+    },
+    client, config,
+    util::{
+        ancestors, capture_at, children, end_of_document, get_file_from_db, similar_names, walk_up,
+    },
+};
+
+pub(crate) fn mk_edit(uri: &Url, edits: Vec<TextEdit>) -> WorkspaceEdit {
+    WorkspaceEdit {
+        changes: Some([(uri.clone(), edits)].into()),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn mk_quickfix(
+    uri: &Url,
+    diagnostic: &Diagnostic,
+    title: String,
+    edits: Vec<TextEdit>,
+) -> CodeAction {
+    CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(mk_edit(uri, edits)),
+        ..Default::default()
+    }
+}
+
+// Lets the user pick another name than the suggested one right away, provided the client has
+// a command for it. The command receives the position as `TextDocumentPositionParams`.
+pub(crate) fn with_rename(action: CodeAction, uri: &Url, position: Position) -> CodeAction {
+    CodeAction {
+        command: client::rename_command().map(|command| Command {
+            title: "Rename".into(),
+            command,
+            arguments: Some(vec![
+                serde_json::to_value(TextDocumentPositionParams {
+                    text_document: TextDocumentIdentifier { uri: uri.clone() },
+                    position,
+                })
+                .unwrap(),
+            ]),
+        }),
+        ..action
+    }
+}
+
+fn is_requested(only: &Option<Vec<CodeActionKind>>, kind: &CodeActionKind) -> bool {
+    only.as_ref().is_none_or(|only| {
+        only.iter()
+            .any(|requested| kind.as_str().starts_with(requested.as_str()))
+    })
+}
+
+// Source actions are costly and only computed when asked for, e.g. by "Organize Imports"
+fn is_explicitly_requested(only: &Option<Vec<CodeActionKind>>, kind: &CodeActionKind) -> bool {
+    only.is_some() && is_requested(only, kind)
+}
+
+fn end_of_line(document: &Document, line: u32) -> Option<Position> {
+    Some(Position {
+        line,
+        character: document
+            .texter
+            .get_row(line as usize)?
+            .trim_end_matches(['\r', '\n'])
+            .len() as u32,
+    })
+}
+
+// Extends the range of a declaration to its doc comment and, if the declaration stands on
+// lines of its own, to these lines and one adjacent blank line
+pub(crate) fn removal_range(document: &Document, range: Range) -> Option<Range> {
+    let text = &document.texter;
+    let is_blank = |line: u32| text.get_row(line as usize).map(|row| row.trim().is_empty());
+
+    let before = text
+        .get_row(range.start.line as usize)?
+        .get(..range.start.character as usize)?;
+    let after = text
+        .get_row(range.end.line as usize)?
+        .get(range.end.character as usize..)?;
+    if !before.trim().is_empty() || !after.trim().is_empty() {
+        return Some(range);
+    }
+
+    let start_line = range.start.line - get_doc_lines(text, range.start).ok()?.len() as u32;
+    if text.get_row(range.end.line as usize + 1).is_none() {
+        // There is no line break after the declaration, so remove the one before it
+        return Some(Range {
+            start: match start_line.checked_sub(1) {
+                Some(line) => end_of_line(document, line)?,
+                None => Position::default(),
+            },
+            end: end_of_line(document, range.end.line)?,
+        });
+    }
+
+    let (start_line, end_line) = if is_blank(range.end.line + 1) == Some(true)
+        && text.get_row(range.end.line as usize + 2).is_some()
+    {
+        (start_line, range.end.line + 2)
+    } else if start_line > 0 && is_blank(start_line - 1) == Some(true) {
+        (start_line - 1, range.end.line + 1)
+    } else {
+        (start_line, range.end.line + 1)
+    };
+
+    Some(Range {
+        start: Position {
+            line: start_line,
+            character: 0,
+        },
+        end: Position {
+            line: end_line,
+            character: 0,
+        },
+    })
+}
+
+// Finds the typeref reported by a diagnostic, given that it does not resolve
+fn unresolved_typeref<'a>(
+    ast: &'a ParsedAst,
+    document: &Document,
+    diagnostic: &Diagnostic,
+) -> Option<&'a Typeref> {
+    let document_bytes = document.as_bytes();
+    let typeref = capture_at::<Typeref>(ast, document, diagnostic.range.start)?;
+    let name = typeref.children.cast(ast);
+    if name.get_lsp_range(document).unwrap() != diagnostic.range {
+        return None;
+    }
+
+    let name = name.get_text(document_bytes).unwrap();
+    if ast
+        .iter()
+        .filter_map(|node| node.lower().downcast_ref::<Typedef>())
+        .any(|typedef| typedef.name.cast(ast).get_text(document_bytes).unwrap() == name)
+    {
+        None
+    } else {
+        Some(typeref)
+    }
+}
+
+// Inserts a declaration after the one containing the given node, on the same level
+pub(crate) fn insert_declaration(
+    ast: &ParsedAst,
+    document: &Document,
+    node: &dyn AstNode,
+    declaration: &str,
+) -> Option<TextEdit> {
+    let enclosing = ancestors(ast, node)
+        .find(|node| node.is::<Typedef>() || node.is::<Method>() || node.is::<Error>())?;
+    let range = enclosing.get_lsp_range(document).unwrap();
+    let indent = document
+        .texter
+        .get_row(range.start.line as usize)?
+        .get(..range.start.character as usize)?;
+    let end_of_line = end_of_line(document, range.end.line)?;
+
+    Some(TextEdit::new(
+        Range {
+            start: end_of_line,
+            end: end_of_line,
+        },
+        declaration
+            .lines()
+            .map(|line| format!("\n{}{}", if line.is_empty() { "" } else { indent }, line))
+            .fold("\n".to_string(), |text, line| text + &line),
+    ))
+}
+
+fn create_type(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Option<CodeAction> {
+    if !UNKNOWN_TYPE.matches(diagnostic) {
+        return None;
+    }
+
+    let document_bytes = document.as_bytes();
+    let typeref = unresolved_typeref(ast, document, diagnostic)?;
+    let name = typeref.get_text(document_bytes).unwrap();
+
+    Some(mk_quickfix(
+        uri,
+        diagnostic,
+        format!("Create type `{}`", name),
+        vec![insert_declaration(
+            ast,
+            document,
+            typeref,
+            &format!("type {} ()", name),
+        )?],
+    ))
+}
+
+fn replace_type(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Vec<CodeAction> {
+    if !UNKNOWN_TYPE.matches(diagnostic) {
+        return Vec::new();
+    }
+
+    let document_bytes = document.as_bytes();
+    let Some(typeref) = unresolved_typeref(ast, document, diagnostic) else {
+        return Vec::new();
+    };
+
+    let suggestions = similar_names(
+        typeref.get_text(document_bytes).unwrap(),
+        ast.iter()
+            .filter_map(|node| node.lower().downcast_ref::<Typedef>())
+            .map(|typedef| typedef.name.cast(ast).get_text(document_bytes).unwrap()),
+    );
+    let is_preferred = suggestions.len() == 1;
+
+    suggestions
+        .into_iter()
+        .map(|suggestion| CodeAction {
+            is_preferred: Some(is_preferred),
+            ..mk_quickfix(
+                uri,
+                diagnostic,
+                format!("Change to `{}`", suggestion),
+                vec![TextEdit::new(diagnostic.range, suggestion.to_string())],
+            )
+        })
+        .collect()
+}
+
+// Copies the parameters of an error, or the input or output of a method, referred to as a type
+// into a new type
+fn convert_to_type(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Vec<CodeAction> {
+    if !WRONG_KIND.matches(diagnostic) {
+        return Vec::new();
+    }
+
+    let document_bytes = document.as_bytes();
+    let Some(typeref) = capture_at::<Typeref>(ast, document, diagnostic.range.start) else {
+        return Vec::new();
+    };
+    let name = typeref.get_text(document_bytes).unwrap();
+
+    // The declaration, its structs which could be meant and the suffixes of the new types
+    let mut candidates: Vec<(&dyn AstNode, &dyn AstNode, &str, &str)> = Vec::new();
+    if let Some(error) = ast
+        .iter()
+        .filter_map(|node| node.lower().downcast_ref::<Error>())
+        .find(|error| error.name.cast(ast).get_text(document_bytes).unwrap() == name)
+    {
+        candidates.extend(
+            children(ast, error)
+                .filter(|node| node.is::<Struct>())
+                .map(|parameters| (error as &dyn AstNode, parameters, "parameters", "Info")),
+        );
+    } else if let Some(method) = ast
+        .iter()
+        .filter_map(|node| node.lower().downcast_ref::<Method>())
+        .find(|method| method.name.cast(ast).get_text(document_bytes).unwrap() == name)
+    {
+        let mut structs: Vec<_> = children(ast, method)
+            .filter(|node| node.is::<Struct>())
+            .collect();
+        structs.sort_by_key(|node| node.get_lsp_range(document).unwrap().start);
+        candidates.extend(
+            structs
+                .into_iter()
+                .zip([("input", "Input"), ("output", "Output")])
+                .map(|(node, (part, suffix))| (method as &dyn AstNode, node, part, suffix)),
+        );
+    }
+
+    candidates
+        .into_iter()
+        .filter(|(_, node, _, _)| !node.downcast_ref::<Struct>().unwrap().member.is_empty())
+        .filter_map(|(declaration, node, part, suffix)| {
+            let kind = if declaration.is::<Error>() {
+                "error"
+            } else {
+                "method"
+            };
+            let level = declaration.get_lsp_range(document).unwrap().start.character as usize;
+            let body = fix_indent(node.get_text(document_bytes).unwrap(), level);
+            let new_name = unique_type_name(ast, document_bytes, format!("{}{}", name, suffix));
+
+            let action = mk_quickfix(
+                uri,
+                diagnostic,
+                format!(
+                    "Convert {} of {} `{}` into type `{}`",
+                    part, kind, name, new_name
+                ),
+                vec![
+                    TextEdit::new(diagnostic.range, new_name.clone()),
+                    insert_declaration(
+                        ast,
+                        document,
+                        typeref,
+                        &format!("type {} {}", new_name, body.trim_start()),
+                    )?,
+                ],
+            );
+            Some(with_rename(action, uri, diagnostic.range.start))
+        })
+        .collect()
+}
+
+fn insert_trailing_newline(
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Option<CodeAction> {
+    if !TRAILING_NEWLINE.matches(diagnostic) {
+        return None;
+    }
+
+    let end = end_of_document(document);
+    if document.texter.text.ends_with('\n') || diagnostic.range != (Range { start: end, end }) {
+        return None;
+    }
+
+    Some(CodeAction {
+        is_preferred: Some(true),
+        ..mk_quickfix(
+            uri,
+            diagnostic,
+            "Insert trailing newline".into(),
+            vec![TextEdit::new(diagnostic.range, "\n".into())],
+        )
+    })
+}
+
+// Removes the unused type together with the unused types referring to it, directly or not
+fn remove_unused_type(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Option<CodeAction> {
+    if !UNUSED_TYPE.matches(diagnostic) {
+        return None;
+    }
+
+    let document_bytes = document.as_bytes();
+    let typedef = capture_at::<Typedef>(ast, document, diagnostic.range.start)?;
+    let name = typedef.name.cast(ast);
+    if name.get_lsp_range(document).unwrap() != diagnostic.range {
+        return None;
+    }
+
+    let name = name.get_text(document_bytes).unwrap();
+    let errors_are_roots = config::for_document(uri).errors_are_roots();
+    if reachable_types(ast, document_bytes, errors_are_roots).contains(name) {
+        return None;
+    }
+
+    // Users of unreachable types are unreachable themselves, except for errors which are not
+    // considered roots
+    let mut group = HashSet::from([name]);
+    let mut stack = vec![name];
+    let mut used_by_error = false;
+    while let Some(current) = stack.pop() {
+        for typeref in ast
+            .iter()
+            .filter_map(|node| node.lower().downcast_ref::<Typeref>())
+            .filter(|typeref| typeref.get_text(document_bytes).unwrap() == current)
+        {
+            let Some(user) = walk_up::<Typedef>(ast, typeref) else {
+                used_by_error = true;
+                continue;
+            };
+            let user = user.name.cast(ast).get_text(document_bytes).unwrap();
+            if group.insert(user) {
+                stack.push(user);
+            }
+        }
+    }
+
+    // Removing the types would leave the error with references to undeclared types, so it is up
+    // to the user to change the error first
+    if used_by_error {
+        return None;
+    }
+
+    let mut ranges = vec![removal_range(
+        document,
+        typedef.get_lsp_range(document).unwrap(),
+    )?];
+    for other in ast
+        .iter()
+        .filter_map(|node| node.lower().downcast_ref::<Typedef>())
+        .filter(|other| !std::ptr::eq(*other, typedef))
+    {
+        let other_name = other.name.cast(ast).get_text(document_bytes).unwrap();
+        if other_name != name && group.contains(other_name) {
+            ranges.push(removal_range(
+                document,
+                other.get_lsp_range(document).unwrap(),
+            )?);
+        }
+    }
+
+    // Removals of adjacent declarations may both claim the blank line between them
+    ranges.sort_by_key(|range| (range.start, range.end));
+    let mut end = Position::default();
+    let edits = ranges
+        .into_iter()
+        .filter_map(|mut range| {
+            range.start = range.start.max(end);
+            end = end.max(range.end);
+            (range.start < range.end).then(|| TextEdit::new(range, String::new()))
+        })
+        .collect();
+
+    let mut others: Vec<String> = group
+        .iter()
+        .filter(|other| **other != name)
+        .map(|other| format!("`{}`", other))
+        .collect();
+    others.sort();
+    let title = match others.len() {
+        0 => format!("Remove unused type `{}`", name),
+        _ => format!(
+            "Remove unused type `{}` and its users {}",
+            name,
+            join_names(&others)
+        ),
+    };
+
+    Some(CodeAction {
+        is_preferred: Some(true),
+        ..mk_quickfix(uri, diagnostic, title, edits)
+    })
+}
+
+// Renames the declaration or member to follow the naming convention, which also makes names
+// valid that the specification rejects
+fn rename_to_convention(
+    db: &impl BaseDatabase,
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Option<CodeAction> {
+    if !INVALID_NAME.matches(diagnostic)
+        && !naming::LINTS.iter().any(|lint| lint.matches(diagnostic))
+    {
+        return None;
+    }
+
+    let range = diagnostic.range;
+    let (lint, name) = naming::name_at(ast, document, range)?;
+    let new_name = naming::suggest_name(lint, name)?;
+
+    let edit = rename(
+        db,
+        RenameParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position: range.start,
+            },
+            new_name: new_name.clone(),
+            work_done_progress_params: Default::default(),
+        },
+    )
+    .ok()??;
+
+    Some(CodeAction {
+        title: format!("Rename to `{}`", new_name),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(edit),
+        ..Default::default()
+    })
+}
+
+fn quickfixes(
+    db: &impl BaseDatabase,
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Vec<CodeAction> {
+    let mut actions = Vec::new();
+    actions.extend(insert_trailing_newline(document, uri, diagnostic));
+    actions.extend(remove_unused_type(ast, document, uri, diagnostic));
+    actions.extend(replace_type(ast, document, uri, diagnostic));
+    actions.extend(create_type(ast, document, uri, diagnostic));
+    actions.extend(convert_to_type(ast, document, uri, diagnostic));
+    actions.extend(extract_duplicates(
+        ast,
+        document,
+        uri,
+        diagnostic,
+        config::for_document(uri).duplicate_min_size(),
+    ));
+    actions.extend(extract_complex(ast, document, uri, diagnostic));
+    actions.extend(rename_to_convention(db, ast, document, uri, diagnostic));
+    actions.extend(insert_doc(ast, document, uri, diagnostic));
+    actions.extend(attach_doc(ast, document, uri, diagnostic));
+    actions.extend(infer_from_sample(ast, document, uri, diagnostic));
+    actions.extend(suppress(ast, document, uri, diagnostic));
+    actions
+}
+
+// Combines the preferred fixes for all diagnostics of the document
+fn fix_all(
+    db: &impl BaseDatabase,
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostics: &[Diagnostic],
+) -> Option<CodeAction> {
+    let mut edits: Vec<TextEdit> = diagnostics
+        .iter()
+        .flat_map(|diagnostic| quickfixes(db, ast, document, uri, diagnostic))
+        .filter(|action| action.is_preferred == Some(true))
+        .filter_map(|action| action.edit?.changes?.remove(uri))
+        .flatten()
+        .collect();
+    edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+
+    // Edits must not overlap
+    let mut end = Position::default();
+    edits.retain(|edit| {
+        if edit.range.start < end {
+            false
+        } else {
+            end = edit.range.end;
+            true
+        }
+    });
+
+    if edits.is_empty() {
+        return None;
+    }
+
+    Some(CodeAction {
+        title: "Fix all in file".into(),
+        kind: Some(CodeActionKind::SOURCE_FIX_ALL),
+        edit: Some(mk_edit(uri, edits)),
+        ..Default::default()
+    })
+}
+
+pub fn code_actions(
+    db: &impl BaseDatabase,
+    params: CodeActionParams,
+) -> anyhow::Result<Option<CodeActionResponse>> {
+    let uri = &params.text_document.uri;
+    let file = get_file_from_db(uri, db)?;
+    let ast = get_ast(db, file);
+    let document = file.document(db);
+    let only = &params.context.only;
+
+    let mut actions = Vec::new();
+    params.context.diagnostics.iter().for_each(|diagnostic| {
+        actions.extend(quickfixes(db, ast, document, uri, diagnostic));
+    });
+
+    actions.extend(extract_type(ast, document, uri, params.range));
+    actions.extend(inline_type(ast, document, uri, params.range));
+    actions.extend(type_modifiers(ast, document, uri, params.range));
+    actions.extend(infer_type(ast, document, uri, params.range));
+
+    if is_explicitly_requested(only, &CodeActionKind::SOURCE_ORGANIZE_IMPORTS) {
+        actions.extend(organize_declarations(
+            ast,
+            document,
+            uri,
+            &params.context.diagnostics,
+        ));
+    }
+
+    if is_explicitly_requested(only, &CodeActionKind::SOURCE_FIX_ALL) {
+        actions.extend(fix_all(db, ast, document, uri, &_diagnostics(db, &file)));
+    }
+
+    Ok(Some(
+        actions
+            .into_iter()
+            .filter(|action| {
+                action
+                    .kind
+                    .as_ref()
+                    .is_none_or(|kind| is_requested(only, kind))
+            })
+            .map(CodeActionOrCommand::CodeAction)
+            .collect(),
+    ))
+}
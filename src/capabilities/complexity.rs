@@ -0,0 +1,151 @@
+use auto_lsp::{
+    core::{ast::AstNode, document::Document},
+    default::db::tracked::ParsedAst,
+    lsp_types::{CodeAction, Diagnostic, DiagnosticSeverity, Url},
+};
+
+use crate::{
+    ast::{Enum, Struct},
+    capabilities::{
+        code_actions::{mk_quickfix, with_rename},
+        diagnostics::Lint,
+        extract_type::{extract, is_anonymous, suggest_type_name},
+    },
+    config::Config,
+    util::walk_up,
+};
+
+pub const NESTING_LINT: Lint = Lint {
+    name: "nesting-depth",
+    code: "VL0020",
+    opt_in: false,
+};
+pub const FIELDS_LINT: Lint = Lint {
+    name: "too-many-fields",
+    code: "VL0021",
+    opt_in: true,
+};
+pub const MEMBERS_LINT: Lint = Lint {
+    name: "too-many-members",
+    code: "VL0022",
+    opt_in: true,
+};
+
+// Number of anonymous types the node is nested in, including itself
+fn depth(ast: &ParsedAst, node: &dyn AstNode) -> usize {
+    let outer = node
+        .get_parent(ast)
+        .and_then(|parent| walk_up::<Struct>(ast, parent.lower()));
+    usize::from(is_anonymous(ast, node)) + outer.map_or(0, |outer| depth(ast, outer))
+}
+
+fn mk_diagnostic(
+    lint: &Lint,
+    node: &dyn AstNode,
+    document: &Document,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        range: node.get_lsp_range(document).unwrap(),
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(lint.to_code()),
+        message,
+        ..Diagnostic::default()
+    }
+}
+
+pub(crate) fn check_complexity(
+    ast: &ParsedAst,
+    document: &Document,
+    config: &Config,
+) -> Vec<Diagnostic> {
+    let max_depth = config.max_nesting_depth();
+    let max_fields = config.max_struct_fields();
+    let max_members = config.max_enum_members();
+
+    let mut items = Vec::new();
+    // Declared types are the result of extracting, so only anonymous ones are reported
+    ast.iter()
+        .map(|node| node.lower())
+        .filter(|node| is_anonymous(ast, *node))
+        .for_each(|node| {
+            // Only the outermost type that is too deep, as extracting it fixes the ones inside
+            if config.is_enabled(&NESTING_LINT) && depth(ast, node) == max_depth + 1 {
+                let kind = if node.is::<Enum>() { "enum" } else { "struct" };
+                items.push(mk_diagnostic(
+                    &NESTING_LINT,
+                    node,
+                    document,
+                    format!(
+                        "anonymous {} is nested more than {} levels deep",
+                        kind, max_depth
+                    ),
+                ));
+            }
+
+            if let Some(struct_) = node.downcast_ref::<Struct>()
+                && config.is_enabled(&FIELDS_LINT)
+                && struct_.member.len() > max_fields
+            {
+                items.push(mk_diagnostic(
+                    &FIELDS_LINT,
+                    node,
+                    document,
+                    format!(
+                        "anonymous struct has {} fields, more than {}",
+                        struct_.member.len(),
+                        max_fields
+                    ),
+                ));
+            }
+
+            if let Some(enum_) = node.downcast_ref::<Enum>()
+                && config.is_enabled(&MEMBERS_LINT)
+                && enum_.member.len() > max_members
+            {
+                items.push(mk_diagnostic(
+                    &MEMBERS_LINT,
+                    node,
+                    document,
+                    format!(
+                        "anonymous enum has {} members, more than {}",
+                        enum_.member.len(),
+                        max_members
+                    ),
+                ));
+            }
+        });
+
+    items
+}
+
+// Moves the reported anonymous type into a declaration of its own
+pub(crate) fn extract_complex(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Option<CodeAction> {
+    if ![NESTING_LINT, FIELDS_LINT, MEMBERS_LINT]
+        .iter()
+        .any(|lint| lint.matches(diagnostic))
+    {
+        return None;
+    }
+
+    let node = ast.iter().map(|node| node.lower()).find(|node| {
+        is_anonymous(ast, *node) && node.get_lsp_range(document).unwrap() == diagnostic.range
+    })?;
+    let name = suggest_type_name(ast, document.as_bytes(), node);
+
+    Some(with_rename(
+        mk_quickfix(
+            uri,
+            diagnostic,
+            format!("Extract type `{}`", name),
+            extract(ast, document, node, &name)?,
+        ),
+        uri,
+        diagnostic.range.start,
+    ))
+}
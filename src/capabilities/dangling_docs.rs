@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use auto_lsp::{
+    core::{ast::AstNode, document::Document},
+    default::db::tracked::ParsedAst,
+    lsp_types::{CodeAction, Diagnostic, DiagnosticSeverity, Position, Range, TextEdit, Url},
+};
+
+use crate::{
+    ast::{InterfaceDeclaration, StructField},
+    capabilities::{code_actions::mk_quickfix, diagnostics::Lint},
+    util::{declarations, indentation},
+};
+
+pub const LINT: Lint = Lint {
+    name: "dangling-doc",
+    code: "VL0018",
+    opt_in: false,
+};
+
+// A comment block that would document a declaration or field if it were attached
+struct Dangling<'a> {
+    range: Range,
+    name: &'a str,
+    separated: bool,
+    edits: Vec<TextEdit>,
+}
+
+fn comment_column(row: &str) -> Option<usize> {
+    let column = indentation(row).len();
+    row[column..].starts_with('#').then_some(column)
+}
+
+fn is_blank(row: &str) -> bool {
+    row.trim().is_empty()
+}
+
+fn find_dangling<'a>(ast: &'a ParsedAst, document: &'a Document) -> Vec<Dangling<'a>> {
+    let document_bytes = document.as_bytes();
+    let rows: Vec<&str> = document.texter.text.lines().collect();
+
+    // Lines on which a documentable node starts, with its column and name
+    let mut targets: HashMap<u32, (u32, &str, bool)> = HashMap::new();
+    declarations(ast).for_each(|(node, name)| {
+        let start = node.get_start_position();
+        targets.insert(
+            start.line,
+            (
+                start.character,
+                name.get_text(document_bytes).unwrap(),
+                node.is::<InterfaceDeclaration>(),
+            ),
+        );
+    });
+    ast.iter()
+        .filter_map(|node| node.lower().downcast_ref::<StructField>())
+        .for_each(|field| {
+            let start = field.get_start_position();
+            let before = rows
+                .get(start.line as usize)
+                .and_then(|row| row.get(..start.character as usize));
+            if before.is_some_and(is_blank) {
+                targets.entry(start.line).or_insert((
+                    start.character,
+                    field.name.cast(ast).get_text(document_bytes).unwrap(),
+                    false,
+                ));
+            }
+        });
+
+    let mut result = Vec::new();
+    let mut line = 0;
+    while line < rows.len() {
+        if comment_column(rows[line]).is_none() {
+            line += 1;
+            continue;
+        }
+
+        let first = line;
+        while line < rows.len() && comment_column(rows[line]).is_some() {
+            line += 1;
+        }
+        let last = line - 1;
+
+        let target = (line..rows.len()).find(|l| !is_blank(rows[*l]));
+        let Some((target, &(column, name, is_interface))) =
+            target.and_then(|target| Some((target, targets.get(&(target as u32))?)))
+        else {
+            continue;
+        };
+
+        // Headers such as license notices precede the interface declaration
+        let separated = target > line;
+        if separated && is_interface && first == 0 {
+            continue;
+        }
+
+        // Section headers are set apart by more than a single blank line
+        if target - line > 1 {
+            continue;
+        }
+
+        let indent = &rows[target][..column as usize];
+        let mut edits: Vec<TextEdit> = (first..=last)
+            .filter(|l| comment_column(rows[*l]) != Some(column as usize))
+            .map(|l| {
+                TextEdit::new(
+                    Range {
+                        start: Position {
+                            line: l as u32,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: l as u32,
+                            character: comment_column(rows[l]).unwrap() as u32,
+                        },
+                    },
+                    indent.to_string(),
+                )
+            })
+            .collect();
+        if separated {
+            edits.push(TextEdit::new(
+                Range {
+                    start: Position {
+                        line: line as u32,
+                        character: 0,
+                    },
+                    end: Position {
+                        line: target as u32,
+                        character: 0,
+                    },
+                },
+                String::new(),
+            ));
+        }
+
+        if !edits.is_empty() {
+            result.push(Dangling {
+                range: Range {
+                    start: Position {
+                        line: first as u32,
+                        character: comment_column(rows[first]).unwrap() as u32,
+                    },
+                    end: Position {
+                        line: last as u32,
+                        character: rows[last].len() as u32,
+                    },
+                },
+                name,
+                separated,
+                edits,
+            });
+        }
+    }
+
+    result
+}
+
+pub(crate) fn check_dangling_docs(ast: &ParsedAst, document: &Document) -> Vec<Diagnostic> {
+    find_dangling(ast, document)
+        .into_iter()
+        .map(|dangling| Diagnostic {
+            range: dangling.range,
+            severity: Some(DiagnosticSeverity::HINT),
+            code: Some(LINT.to_code()),
+            message: if dangling.separated {
+                format!(
+                    "comment is not attached to `{}` because of the blank line",
+                    dangling.name
+                )
+            } else {
+                format!(
+                    "comment is not attached to `{}` because it is indented differently",
+                    dangling.name
+                )
+            },
+            ..Diagnostic::default()
+        })
+        .collect()
+}
+
+pub(crate) fn attach_doc(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Option<CodeAction> {
+    if !LINT.matches(diagnostic) {
+        return None;
+    }
+
+    let dangling = find_dangling(ast, document)
+        .into_iter()
+        .find(|dangling| dangling.range == diagnostic.range)?;
+
+    Some(mk_quickfix(
+        uri,
+        diagnostic,
+        format!("Attach comment to `{}`", dangling.name),
+        dangling.edits,
+    ))
+}
@@ -1,22 +1,190 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use auto_lsp::core::ast::AstNode;
 use auto_lsp::core::document::Document;
 use auto_lsp::core::errors::ParseErrorAccumulator;
 use auto_lsp::default::db::BaseDatabase;
 use auto_lsp::default::db::file::File;
-use auto_lsp::default::db::tracked::get_ast;
+use auto_lsp::default::db::tracked::{ParsedAst, get_ast};
 use auto_lsp::lsp_types::{
     Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, DocumentDiagnosticParams,
     DocumentDiagnosticReport, DocumentDiagnosticReportResult, FullDocumentDiagnosticReport,
-    Location, Position, Range, RelatedFullDocumentDiagnosticReport, Url, WorkspaceDiagnosticParams,
-    WorkspaceDiagnosticReport, WorkspaceDiagnosticReportResult, WorkspaceDocumentDiagnosticReport,
-    WorkspaceFullDocumentDiagnosticReport,
+    Location, NumberOrString, Position, PublishDiagnosticsParams, Range,
+    RelatedFullDocumentDiagnosticReport, RelatedUnchangedDocumentDiagnosticReport,
+    UnchangedDocumentDiagnosticReport, Url, WorkspaceDiagnosticParams, WorkspaceDiagnosticReport,
+    WorkspaceDiagnosticReportResult, WorkspaceDocumentDiagnosticReport,
+    WorkspaceFullDocumentDiagnosticReport, WorkspaceUnchangedDocumentDiagnosticReport,
 };
-use auto_lsp::{anyhow, lsp_types};
+use auto_lsp::anyhow;
 
-use crate::ast::{Enum, Error, Method, Struct, Typedef, Typeref};
-use crate::util::get_file_from_db;
+use crate::ast::{
+    Array, Enum, Error, InterfaceDeclaration, Map, Maybe, Method, Struct, StructField, Typedef,
+    Typeref,
+};
+use crate::capabilities::complexity::{self, check_complexity};
+use crate::capabilities::dangling_docs::{self, check_dangling_docs};
+use crate::capabilities::documentation::{self, check_documentation};
+use crate::capabilities::duplicates::{self, check_duplicates};
+use crate::capabilities::loose_types::{self, check_loose_types};
+use crate::capabilities::naming::{self, check_naming};
+use crate::capabilities::organize::{self, check_organized};
+use crate::capabilities::suppress::apply_suppressions;
+use crate::client;
+use crate::config::{self, Config};
+use crate::util::{
+    ancestors, end_of_document, get_file_from_db, similar_names, type_dependencies, walk_up,
+};
+
+// How long workspace diagnostics are held back at most, and how often changes are looked for
+const MAX_HOLD: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lint {
+    // Used to configure the lint
+    pub name: &'static str,
+    pub code: &'static str,
+    // Opt-in lints are only reported if enabled in the configuration
+    pub opt_in: bool,
+}
+
+impl Lint {
+    pub fn matches(&self, diagnostic: &Diagnostic) -> bool {
+        diagnostic.code == Some(self.to_code())
+    }
+
+    pub(crate) fn to_code(&self) -> NumberOrString {
+        NumberOrString::String(self.code.into())
+    }
+}
+
+pub const SYNTAX_ERROR: Lint = Lint {
+    name: "syntax-error",
+    code: "VL0001",
+    opt_in: false,
+};
+pub const TRAILING_NEWLINE: Lint = Lint {
+    name: "trailing-newline",
+    code: "VL0002",
+    opt_in: false,
+};
+pub const UNUSED_TYPE: Lint = Lint {
+    name: "unused-type",
+    code: "VL0003",
+    opt_in: false,
+};
+pub const DUPLICATE_DECLARATION: Lint = Lint {
+    name: "duplicate-declaration",
+    code: "VL0004",
+    opt_in: false,
+};
+pub const UNKNOWN_TYPE: Lint = Lint {
+    name: "unknown-type",
+    code: "VL0005",
+    opt_in: false,
+};
+pub const AMBIGUOUS_TYPE: Lint = Lint {
+    name: "ambiguous-type",
+    code: "VL0006",
+    opt_in: false,
+};
+
+pub const INVALID_NAME: Lint = Lint {
+    name: "invalid-name",
+    code: "VL0008",
+    opt_in: false,
+};
+pub const NAME_TOO_LONG: Lint = Lint {
+    name: "name-too-long",
+    code: "VL0009",
+    opt_in: false,
+};
+pub const EMPTY_ENUM: Lint = Lint {
+    name: "empty-enum",
+    code: "VL0010",
+    opt_in: false,
+};
+// Only reserved in some versions of the specification
+pub const RESERVED_NAME: Lint = Lint {
+    name: "reserved-name",
+    code: "VL0011",
+    opt_in: true,
+};
+
+pub const INFINITE_TYPE: Lint = Lint {
+    name: "infinite-type",
+    code: "VL0012",
+    opt_in: false,
+};
+
+pub const NAME_COLLISION: Lint = Lint {
+    name: "name-collision",
+    code: "VL0013",
+    opt_in: false,
+};
+
+pub const WRONG_KIND: Lint = Lint {
+    name: "wrong-kind",
+    code: "VL0014",
+    opt_in: false,
+};
+
+const LINTS: &[Lint] = &[
+    SYNTAX_ERROR,
+    TRAILING_NEWLINE,
+    UNUSED_TYPE,
+    DUPLICATE_DECLARATION,
+    UNKNOWN_TYPE,
+    AMBIGUOUS_TYPE,
+    INVALID_NAME,
+    NAME_TOO_LONG,
+    EMPTY_ENUM,
+    RESERVED_NAME,
+    INFINITE_TYPE,
+    NAME_COLLISION,
+    WRONG_KIND,
+];
+
+pub fn all_lints() -> impl Iterator<Item = &'static Lint> {
+    LINTS
+        .iter()
+        .chain([
+            &complexity::NESTING_LINT,
+            &complexity::FIELDS_LINT,
+            &complexity::MEMBERS_LINT,
+            &dangling_docs::LINT,
+            &documentation::LINT,
+            &documentation::FIELD_LINT,
+            &duplicates::LINT,
+            &loose_types::LINT,
+            &organize::LINT,
+        ])
+        .chain(naming::LINTS.iter())
+}
+
+// Drops disabled diagnostics and applies the configured severities
+fn apply_config(config: &Config, items: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    items
+        .into_iter()
+        .filter_map(|diagnostic| {
+            let Some(lint) = all_lints().find(|lint| lint.matches(&diagnostic)) else {
+                return Some(diagnostic);
+            };
+
+            if !config.is_enabled(lint) {
+                return None;
+            }
+
+            Some(Diagnostic {
+                severity: config.severity(lint).or(diagnostic.severity),
+                ..diagnostic
+            })
+        })
+        .collect()
+}
 
 fn get_parse_errors(db: &impl BaseDatabase, file: File, document: &Document) -> Vec<Diagnostic> {
     let mut error_positions: BTreeSet<(Position, Position)> = BTreeSet::new();
@@ -32,7 +200,7 @@ fn get_parse_errors(db: &impl BaseDatabase, file: File, document: &Document) ->
                 error_positions.insert(position);
                 Some(Diagnostic {
                     message: "syntax error".to_string(),
-                    code: None,
+                    code: Some(SYNTAX_ERROR.to_code()),
                     ..diagnostic
                 })
             }
@@ -42,16 +210,14 @@ fn get_parse_errors(db: &impl BaseDatabase, file: File, document: &Document) ->
 
 fn check_trailing_newline(document: &Document) -> Vec<Diagnostic> {
     if document.texter.text.chars().last().map(|last| last == '\n') == Some(false) {
-        let end_of_document = Position {
-            line: usize::from(document.texter.br_indexes.row_count()) as u32,
-            character: 0,
-        };
+        let end_of_document = end_of_document(document);
         return vec![Diagnostic {
             range: Range {
                 start: end_of_document,
                 end: end_of_document,
             },
             severity: Some(DiagnosticSeverity::WARNING),
+            code: Some(TRAILING_NEWLINE.to_code()),
             message: "missing trailing newline".into(),
             ..Diagnostic::default()
         }];
@@ -74,6 +240,7 @@ fn check_defs(kind: &str, collection: &HashMap<&str, Vec<Range>>, uri: &Url) ->
                         .map(move |(i, occurence)| Diagnostic {
                             range: *occurence,
                             severity: Some(DiagnosticSeverity::ERROR),
+                            code: Some(DUPLICATE_DECLARATION.to_code()),
                             message: format!("{} `{}` declared multiple times", kind, name),
                             related_information: Some({
                                 occurences
@@ -98,8 +265,120 @@ fn check_defs(kind: &str, collection: &HashMap<&str, Vec<Range>>, uri: &Url) ->
         .collect()
 }
 
+// Types, errors and methods share the namespace of the interface
+fn check_collisions(
+    declarations: &[(&str, &HashMap<&str, Vec<Range>>)],
+    uri: &Url,
+) -> Vec<Diagnostic> {
+    let mut names: Vec<&str> = declarations
+        .iter()
+        .flat_map(|(_, collection)| collection.keys().copied())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .flat_map(|name| {
+            let occurences: Vec<(&str, Range)> = declarations
+                .iter()
+                .flat_map(|(kind, collection)| {
+                    collection
+                        .get(name)
+                        .into_iter()
+                        .flatten()
+                        .map(move |range| (*kind, *range))
+                })
+                .collect();
+
+            let mut kinds: Vec<&str> = occurences.iter().map(|(kind, _)| *kind).collect();
+            kinds.dedup();
+            if kinds.len() < 2 {
+                return Vec::new();
+            }
+
+            occurences
+                .iter()
+                .enumerate()
+                .map(|(i, (kind, range))| Diagnostic {
+                    range: *range,
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    code: Some(NAME_COLLISION.to_code()),
+                    message: format!(
+                        "{} `{}` collides with {}",
+                        kind,
+                        name,
+                        kinds
+                            .iter()
+                            .filter(|other| other != &kind)
+                            .map(|other| format!("{} `{}`", other, name))
+                            .collect::<Vec<_>>()
+                            .join(" and ")
+                    ),
+                    related_information: Some(
+                        occurences
+                            .iter()
+                            .enumerate()
+                            .filter(|(j, _)| *j != i)
+                            .map(|(_, (kind, range))| DiagnosticRelatedInformation {
+                                location: Location {
+                                    uri: uri.clone(),
+                                    range: *range,
+                                },
+                                message: format!("declared as {} here", kind),
+                            })
+                            .collect(),
+                    ),
+                    ..Diagnostic::default()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// A reference to an error or method instead of a type
+fn wrong_kind(
+    others: &[(&str, &HashMap<&str, Vec<Range>>)],
+    name: &str,
+    range: Range,
+    uri: &Url,
+) -> Option<Diagnostic> {
+    let (kind, declarations) = others
+        .iter()
+        .find_map(|(kind, collection)| Some((*kind, collection.get(name)?)))?;
+
+    Some(Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(WRONG_KIND.to_code()),
+        message: format!("`{}` is {} {}, not a type", name, article(kind), kind),
+        related_information: Some(
+            declarations
+                .iter()
+                .map(|declaration| DiagnosticRelatedInformation {
+                    location: Location {
+                        uri: uri.clone(),
+                        range: *declaration,
+                    },
+                    message: format!("{} declared here", kind),
+                })
+                .collect(),
+        ),
+        ..Diagnostic::default()
+    })
+}
+
+fn article(word: &str) -> &'static str {
+    if word.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        "an"
+    } else {
+        "a"
+    }
+}
+
 fn check_typerefs(
     typedefs: &HashMap<&str, Vec<Range>>,
+    others: &[(&str, &HashMap<&str, Vec<Range>>)],
     typerefs: &HashMap<&str, Vec<Range>>,
     uri: &Url,
 ) -> Vec<Diagnostic> {
@@ -107,12 +386,30 @@ fn check_typerefs(
         .iter()
         .flat_map(|(name, occurences)| {
             let def = typedefs.get(name);
+            let suggestions = match def {
+                None => similar_names(name, typedefs.keys().copied())
+                    .iter()
+                    .map(|suggestion| format!("`{}`", suggestion))
+                    .collect::<Vec<_>>(),
+                Some(_) => Vec::new(),
+            };
             occurences.iter().filter_map(move |occurence| match def {
-                None => Some(Diagnostic {
-                    range: *occurence,
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    message: format!("unknown type `{}`", name),
-                    ..Diagnostic::default()
+                None => wrong_kind(others, name, *occurence, uri).or_else(|| {
+                    Some(Diagnostic {
+                        range: *occurence,
+                        severity: Some(DiagnosticSeverity::ERROR),
+                        code: Some(UNKNOWN_TYPE.to_code()),
+                        message: if suggestions.is_empty() {
+                            format!("unknown type `{}`", name)
+                        } else {
+                            format!(
+                                "unknown type `{}`, did you mean {}?",
+                                name,
+                                suggestions.join(" or ")
+                            )
+                        },
+                        ..Diagnostic::default()
+                    })
                 }),
                 Some(definitions) => {
                     if definitions.len() == 1 {
@@ -121,6 +418,7 @@ fn check_typerefs(
                         Some(Diagnostic {
                             range: *occurence,
                             severity: Some(DiagnosticSeverity::WARNING),
+                            code: Some(AMBIGUOUS_TYPE.to_code()),
                             message: format!("type `{}` declared multiple times", name),
                             related_information: Some({
                                 definitions
@@ -143,11 +441,432 @@ fn check_typerefs(
         .collect()
 }
 
-fn _diagnostics(db: &impl BaseDatabase, file: &File) -> Vec<Diagnostic> {
+// E.g. "`A`, `B` and `C`"
+pub(crate) fn join_names(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.clone(),
+        [init @ .., last] => format!("{} and {}", init.join(", "), last),
+    }
+}
+
+// Types are used if they are reachable from a method (or an error)
+pub(crate) fn reachable_types<'a>(
+    ast: &'a ParsedAst,
+    document_bytes: &'a [u8],
+    errors_are_roots: bool,
+) -> HashSet<&'a str> {
+    let mut roots: Vec<&str> = ast
+        .iter()
+        .filter_map(|node| node.lower().downcast_ref::<Typeref>())
+        .filter(|typeref| {
+            walk_up::<Typedef>(ast, *typeref).is_none()
+                && (errors_are_roots || walk_up::<Error>(ast, *typeref).is_none())
+        })
+        .map(|typeref| typeref.get_text(document_bytes).unwrap())
+        .collect();
+
+    let dependencies = type_dependencies(ast, document_bytes);
+    let mut reachable = HashSet::new();
+    while let Some(name) = roots.pop() {
+        if reachable.insert(name) {
+            roots.extend(
+                dependencies
+                    .get(name)
+                    .into_iter()
+                    .flatten()
+                    .map(|typeref| typeref.get_text(document_bytes).unwrap()),
+            );
+        }
+    }
+
+    reachable
+}
+
+fn check_unused(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    typedefs: &HashMap<&str, Vec<Range>>,
+    errors_are_roots: bool,
+) -> Vec<Diagnostic> {
+    let document_bytes = document.as_bytes();
+
+    // Typerefs outside of the roots together with the declaration containing them
+    let mut users: HashMap<&str, Vec<(String, &Typeref)>> = HashMap::new();
+    ast.iter().for_each(|node| {
+        let Some(typeref) = node.lower().downcast_ref::<Typeref>() else {
+            return;
+        };
+
+        let name = typeref.get_text(document_bytes).unwrap();
+        if let Some(typedef) = walk_up::<Typedef>(ast, typeref) {
+            let user = typedef.name.cast(ast).get_text(document_bytes).unwrap();
+            users
+                .entry(name)
+                .or_default()
+                .push((format!("`{}`", user), typeref));
+        } else if let Some(error) = walk_up::<Error>(ast, typeref)
+            && !errors_are_roots
+        {
+            let user = error.name.cast(ast).get_text(document_bytes).unwrap();
+            users
+                .entry(name)
+                .or_default()
+                .push((format!("error `{}`", user), typeref));
+        }
+    });
+
+    let reachable = reachable_types(ast, document_bytes, errors_are_roots);
+    typedefs
+        .iter()
+        .filter(|(name, _)| !reachable.contains(*name))
+        .flat_map(|(name, defs)| {
+            let users = users.get(name).map(Vec::as_slice).unwrap_or_default();
+            let mut user_names: Vec<String> = users.iter().map(|(user, _)| user.clone()).collect();
+            user_names.sort();
+            user_names.dedup();
+
+            let message = match user_names.len() {
+                0 => format!("type `{}` is not used", name),
+                1 => format!(
+                    "type `{}` is only used by {}, which is itself unused",
+                    name, user_names[0]
+                ),
+                _ => format!(
+                    "type `{}` is only used by {}, which are themselves unused",
+                    name,
+                    join_names(&user_names)
+                ),
+            };
+            let related_information: Vec<DiagnosticRelatedInformation> = users
+                .iter()
+                .map(|(user, typeref)| DiagnosticRelatedInformation {
+                    location: Location {
+                        uri: uri.clone(),
+                        range: typeref.get_lsp_range(document).unwrap(),
+                    },
+                    message: format!("used by {} here", user),
+                })
+                .collect();
+
+            defs.iter().map(move |range| Diagnostic {
+                range: *range,
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(UNUSED_TYPE.to_code()),
+                message: message.clone(),
+                related_information: (!related_information.is_empty())
+                    .then(|| related_information.clone()),
+                ..Diagnostic::default()
+            })
+        })
+        .collect()
+}
+
+// Whether a value of the referenced type may be absent, so that no value is required
+fn is_indirect(ast: &ParsedAst, typeref: &Typeref) -> bool {
+    ancestors(ast, typeref)
+        .take_while(|node| !node.is::<Typedef>())
+        .any(|node| node.is::<Maybe>() || node.is::<Array>() || node.is::<Map>())
+}
+
+// Finds the shortest chain of typerefs leading from one type to another
+fn find_path<'a>(
+    edges: &HashMap<&'a str, Vec<(&'a str, &'a Typeref)>>,
+    from: &'a str,
+    to: &'a str,
+) -> Option<Vec<(&'a str, &'a Typeref)>> {
+    let mut previous: HashMap<&str, (&str, &Typeref)> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(current) = queue.pop_front() {
+        for (next, typeref) in edges.get(current).into_iter().flatten() {
+            if previous.contains_key(next) || *next == from {
+                continue;
+            }
+
+            previous.insert(*next, (current, *typeref));
+            if *next == to {
+                let mut path = Vec::new();
+                let mut current = to;
+                while let Some((before, typeref)) = previous.get(current) {
+                    path.push((*before, *typeref));
+                    if *before == from {
+                        break;
+                    }
+                    current = *before;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            queue.push_back(*next);
+        }
+    }
+
+    None
+}
+
+// Types which contain themselves without indirection cannot be constructed
+fn check_recursion(ast: &ParsedAst, document: &Document, uri: &Url) -> Vec<Diagnostic> {
+    let document_bytes = document.as_bytes();
+    let mut edges: HashMap<&str, Vec<(&str, &Typeref)>> = HashMap::new();
+    ast.iter().for_each(|node| {
+        if let Some(typeref) = node.lower().downcast_ref::<Typeref>()
+            && let Some(typedef) = walk_up::<Typedef>(ast, typeref)
+            && !is_indirect(ast, typeref)
+        {
+            edges
+                .entry(typedef.name.cast(ast).get_text(document_bytes).unwrap())
+                .or_default()
+                .push((typeref.get_text(document_bytes).unwrap(), typeref));
+        }
+    });
+
+    edges
+        .iter()
+        .flat_map(|(from, targets)| targets.iter().map(move |target| (*from, *target)))
+        .filter_map(|(from, (to, typeref))| {
+            let path = if from == to {
+                Vec::new()
+            } else {
+                find_path(&edges, to, from)?
+            };
+
+            let cycle = std::iter::once(from)
+                .chain(std::iter::once(to))
+                .chain(
+                    path.iter()
+                        .map(|(_, typeref)| typeref.get_text(document_bytes).unwrap()),
+                )
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+
+            Some(Diagnostic {
+                range: typeref.get_lsp_range(document).unwrap(),
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(INFINITE_TYPE.to_code()),
+                message: format!(
+                    "type `{}` is infinitely recursive ({}), consider `?{}`",
+                    from, cycle, to
+                ),
+                related_information: Some(
+                    path.iter()
+                        .map(|(before, typeref)| DiagnosticRelatedInformation {
+                            location: Location {
+                                uri: uri.clone(),
+                                range: typeref.get_lsp_range(document).unwrap(),
+                            },
+                            message: format!(
+                                "`{}` refers to `{}` here",
+                                before,
+                                typeref.get_text(document_bytes).unwrap()
+                            ),
+                        })
+                        .collect(),
+                ),
+                ..Diagnostic::default()
+            })
+        })
+        .collect()
+}
+
+// Limit of the reference implementation
+const MAX_NAME_LENGTH: usize = 255;
+
+const RESERVED_NAMES: &[&str] = &[
+    "bool",
+    "int",
+    "float",
+    "string",
+    "object",
+    "any",
+    "type",
+    "method",
+    "error",
+    "interface",
+];
+
+// Reverse-domain, e.g. `org.example.foo`, as required by the specification:
+// `[A-Za-z]([-]*[A-Za-z0-9])*(\.[A-Za-z0-9]([-]*[A-Za-z0-9])*)+`
+pub(crate) fn interface_name_error(name: &str) -> Option<String> {
+    if !name.contains('.') {
+        return Some(format!("interface name `{}` must contain a dot", name));
+    }
+
+    name.split('.').enumerate().find_map(|(i, segment)| {
+        if i == 0 && !segment.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            Some(format!(
+                "interface name `{}` must start with a letter",
+                name
+            ))
+        } else if !segment.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+            Some(format!(
+                "segment `{}` of interface name `{}` must start with a letter or digit",
+                segment, name
+            ))
+        } else if segment.ends_with('-') {
+            Some(format!(
+                "segment `{}` of interface name `{}` must not end with a hyphen",
+                segment, name
+            ))
+        } else if !segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            Some(format!(
+                "segment `{}` of interface name `{}` must only contain letters, digits and hyphens",
+                segment, name
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+// `[A-Z][A-Za-z0-9]*`
+pub(crate) fn member_name_error(kind: &str, name: &str) -> Option<String> {
+    if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+        Some(format!(
+            "{} `{}` must start with an uppercase letter",
+            kind, name
+        ))
+    } else if !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Some(format!(
+            "{} `{}` must only contain letters and digits",
+            kind, name
+        ))
+    } else {
+        None
+    }
+}
+
+// `[A-Za-z](_?[A-Za-z0-9])*`
+pub(crate) fn field_name_error(kind: &str, name: &str) -> Option<String> {
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        Some(format!("{} `{}` must start with a letter", kind, name))
+    } else if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Some(format!(
+            "{} `{}` must only contain letters, digits and underscores",
+            kind, name
+        ))
+    } else if name.ends_with('_') || name.contains("__") {
+        Some(format!(
+            "{} `{}` must not end with or repeat an underscore",
+            kind, name
+        ))
+    } else {
+        None
+    }
+}
+
+fn check_name(
+    name: &dyn AstNode,
+    document: &Document,
+    validate: impl Fn(&str) -> Option<String>,
+) -> Vec<Diagnostic> {
+    let Ok(text) = name.get_text(document.as_bytes()) else {
+        return Vec::new();
+    };
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    let range = name.get_lsp_range(document).unwrap();
+    let mut items = Vec::new();
+    if let Some(message) = validate(text) {
+        items.push(Diagnostic {
+            range,
+            severity: Some(DiagnosticSeverity::ERROR),
+            code: Some(INVALID_NAME.to_code()),
+            message,
+            ..Diagnostic::default()
+        });
+    }
+
+    if text.len() > MAX_NAME_LENGTH {
+        items.push(Diagnostic {
+            range,
+            severity: Some(DiagnosticSeverity::ERROR),
+            code: Some(NAME_TOO_LONG.to_code()),
+            message: format!(
+                "name is {} characters long, at most {} are allowed",
+                text.len(),
+                MAX_NAME_LENGTH
+            ),
+            ..Diagnostic::default()
+        });
+    }
+
+    if RESERVED_NAMES.contains(&text) {
+        items.push(Diagnostic {
+            range,
+            severity: Some(DiagnosticSeverity::WARNING),
+            code: Some(RESERVED_NAME.to_code()),
+            message: format!("`{}` is a reserved word", text),
+            ..Diagnostic::default()
+        });
+    }
+
+    items
+}
+
+// Rules of the specification which the grammar does not enforce
+fn check_conformance(ast: &ParsedAst, document: &Document) -> Vec<Diagnostic> {
+    ast.iter()
+        .flat_map(|node| {
+            let node = node.lower();
+            if let Some(interface) = node.downcast_ref::<InterfaceDeclaration>() {
+                check_name(interface.name.cast(ast), document, interface_name_error)
+            } else if let Some(typedef) = node.downcast_ref::<Typedef>() {
+                check_name(typedef.name.cast(ast), document, |name| {
+                    member_name_error("type name", name)
+                })
+            } else if let Some(method) = node.downcast_ref::<Method>() {
+                check_name(method.name.cast(ast), document, |name| {
+                    member_name_error("method name", name)
+                })
+            } else if let Some(error) = node.downcast_ref::<Error>() {
+                check_name(error.name.cast(ast), document, |name| {
+                    member_name_error("error name", name)
+                })
+            } else if let Some(field) = node.downcast_ref::<StructField>() {
+                check_name(field.name.cast(ast), document, |name| {
+                    field_name_error("field name", name)
+                })
+            } else if let Some(enum_) = node.downcast_ref::<Enum>() {
+                if enum_.member.is_empty() {
+                    return vec![Diagnostic {
+                        range: enum_.get_lsp_range(document).unwrap(),
+                        severity: Some(DiagnosticSeverity::ERROR),
+                        code: Some(EMPTY_ENUM.to_code()),
+                        message: "enum must have at least one member".into(),
+                        ..Diagnostic::default()
+                    }];
+                }
+
+                enum_
+                    .member
+                    .iter()
+                    .flat_map(|member| {
+                        check_name(member.cast(ast), document, |name| {
+                            field_name_error("enum member", name)
+                        })
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn _diagnostics(db: &impl BaseDatabase, file: &File) -> Vec<Diagnostic> {
     let ast = get_ast(db, *file);
     let document = file.document(db);
     let uri = file.url(db);
     let document_bytes = document.as_bytes();
+    let config = config::for_document(uri);
 
     let mut items: Vec<Diagnostic> = Vec::new();
     items.append(&mut get_parse_errors(db, *file, document));
@@ -203,6 +922,14 @@ fn _diagnostics(db: &impl BaseDatabase, file: &File) -> Vec<Diagnostic> {
     };
 
     items.append(&mut check_defs("method", &methods, uri));
+    items.append(&mut check_collisions(
+        &[
+            ("type", &typedefs),
+            ("error", &errors),
+            ("method", &methods),
+        ],
+        uri,
+    ));
 
     let typerefs = {
         let mut result = HashMap::new();
@@ -219,24 +946,19 @@ fn _diagnostics(db: &impl BaseDatabase, file: &File) -> Vec<Diagnostic> {
         result
     };
 
-    items.append(&mut check_typerefs(&typedefs, &typerefs, uri));
-    items.extend(
-        typedefs
-            .iter()
-            .filter_map(|(name, defs)| {
-                if typerefs.contains_key(name) {
-                    None
-                } else {
-                    Some(defs.iter().map(move |range| Diagnostic {
-                        range: *range,
-                        severity: Some(DiagnosticSeverity::WARNING),
-                        message: format!("type `{}` is not used", name),
-                        ..Diagnostic::default()
-                    }))
-                }
-            })
-            .flatten(),
-    );
+    items.append(&mut check_typerefs(
+        &typedefs,
+        &[("error", &errors), ("method", &methods)],
+        &typerefs,
+        uri,
+    ));
+    items.append(&mut check_unused(
+        ast,
+        document,
+        uri,
+        &typedefs,
+        config.errors_are_roots(),
+    ));
 
     ast.iter().for_each(|node| {
         if let Some(struct_) = node.lower().downcast_ref::<Struct>() {
@@ -268,7 +990,43 @@ fn _diagnostics(db: &impl BaseDatabase, file: &File) -> Vec<Diagnostic> {
         }
     });
 
-    items
+    items.append(&mut check_conformance(ast, document));
+    items.append(&mut check_recursion(ast, document, uri));
+    if config.is_enabled(&duplicates::LINT) {
+        items.append(&mut check_duplicates(
+            ast,
+            document,
+            uri,
+            config.duplicate_min_size(),
+        ));
+    }
+    items.append(&mut check_complexity(ast, document, &config));
+    items.append(&mut check_naming(ast, document, &config));
+    items.append(&mut check_documentation(ast, document, &config));
+    if config.is_enabled(&dangling_docs::LINT) {
+        items.append(&mut check_dangling_docs(ast, document));
+    }
+    if config.is_enabled(&loose_types::LINT) {
+        items.append(&mut check_loose_types(ast, document));
+    }
+    if config.is_enabled(&organize::LINT) {
+        items.append(&mut check_organized(
+            ast,
+            document,
+            config.declaration_order(),
+        ));
+    }
+
+    apply_suppressions(ast, document, apply_config(&config, items))
+}
+
+// Identifies the revision of a document and the configuration for which diagnostics were
+// reported
+fn result_id(document: &Document) -> String {
+    let mut hasher = DefaultHasher::new();
+    document.texter.text.hash(&mut hasher);
+    config::generation().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
 }
 
 pub fn diagnostics(
@@ -276,39 +1034,155 @@ pub fn diagnostics(
     params: DocumentDiagnosticParams,
 ) -> anyhow::Result<DocumentDiagnosticReportResult> {
     let file = get_file_from_db(&params.text_document.uri, db)?;
+    let result_id = result_id(file.document(db));
+    if params.previous_result_id.as_ref() == Some(&result_id) {
+        return Ok(DocumentDiagnosticReportResult::Report(
+            DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+                related_documents: None,
+                unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                    result_id,
+                },
+            }),
+        ));
+    }
+
     let items = _diagnostics(db, &file);
     Ok(DocumentDiagnosticReportResult::Report(
         DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
             related_documents: None,
             full_document_diagnostic_report: FullDocumentDiagnosticReport {
-                result_id: None,
+                result_id: Some(result_id),
                 items,
             },
         }),
     ))
 }
 
-pub fn workspace_diagnostics(
+pub fn publish_diagnostics(
     db: &impl BaseDatabase,
-    _params: WorkspaceDiagnosticParams,
-) -> anyhow::Result<WorkspaceDiagnosticReportResult> {
-    let items: Vec<lsp_types::WorkspaceDocumentDiagnosticReport> = db
-        .get_files()
+    uri: &Url,
+) -> anyhow::Result<PublishDiagnosticsParams> {
+    let file = get_file_from_db(uri, db)?;
+    Ok(PublishDiagnosticsParams {
+        uri: uri.clone(),
+        diagnostics: _diagnostics(db, &file),
+        version: None,
+    })
+}
+
+fn workspace_reports(
+    db: &impl BaseDatabase,
+    previous_result_ids: &HashMap<&Url, &str>,
+) -> Vec<WorkspaceDocumentDiagnosticReport> {
+    db.get_files()
         .iter()
         .map(|file| {
+            let uri = file.url(db).clone();
+            let result_id = result_id(file.document(db));
+            if previous_result_ids.get(&uri) == Some(&result_id.as_str()) {
+                return WorkspaceDocumentDiagnosticReport::Unchanged(
+                    WorkspaceUnchangedDocumentDiagnosticReport {
+                        uri,
+                        version: None,
+                        unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                            result_id,
+                        },
+                    },
+                );
+            }
+
             let items = _diagnostics(db, &file);
             WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
                 version: None,
                 full_document_diagnostic_report: FullDocumentDiagnosticReport {
-                    result_id: None,
+                    result_id: Some(result_id),
                     items,
                 },
-                uri: file.url(db).clone(),
+                uri,
             })
         })
+        .collect()
+}
+
+// Clients ask again as soon as they get an answer, so it is held back while nothing changed.
+// Changing a document cancels the request through the database, upon which the client asks
+// again.
+pub fn workspace_diagnostics(
+    db: &impl BaseDatabase,
+    params: WorkspaceDiagnosticParams,
+) -> anyhow::Result<WorkspaceDiagnosticReportResult> {
+    let previous_result_ids: HashMap<&Url, &str> = params
+        .previous_result_ids
+        .iter()
+        .map(|previous| (&previous.uri, previous.value.as_str()))
         .collect();
 
+    let generation = config::generation();
+    let cancellations = client::cancellations();
+    let deadline = Instant::now() + MAX_HOLD;
+    let mut items = workspace_reports(db, &previous_result_ids);
+    while !items
+        .iter()
+        .any(|item| matches!(item, WorkspaceDocumentDiagnosticReport::Full(_)))
+        && Instant::now() < deadline
+        && client::cancellations() == cancellations
+    {
+        thread::sleep(POLL_INTERVAL);
+        db.unwind_if_revision_cancelled();
+        if config::generation() != generation {
+            items = workspace_reports(db, &previous_result_ids);
+        }
+    }
+
     Ok(WorkspaceDiagnosticReportResult::Report(
         WorkspaceDiagnosticReport { items },
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interface_names() {
+        for name in [
+            "org.example.foo",
+            "com.1password.vault",
+            "io.systemd.Journal",
+            "a.b",
+            "org.exa--mple.foo",
+        ] {
+            assert_eq!(interface_name_error(name), None, "{}", name);
+        }
+
+        for name in [
+            "foo",
+            "1org.example",
+            "org..foo",
+            "org.example.",
+            "org.-example",
+            "org.example-",
+            "org.ex_ample",
+        ] {
+            assert!(interface_name_error(name).is_some(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn member_names() {
+        assert_eq!(member_name_error("type", "Foo2"), None);
+        assert!(member_name_error("type", "foo").is_some());
+        assert!(member_name_error("type", "Foo_Bar").is_some());
+    }
+
+    #[test]
+    fn field_names() {
+        for name in ["foo", "foo_bar", "fooBar", "a1_b2"] {
+            assert_eq!(field_name_error("field", name), None, "{}", name);
+        }
+
+        for name in ["_foo", "1foo", "foo_", "foo__bar", "foo-bar"] {
+            assert!(field_name_error("field", name).is_some(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use auto_lsp::{
+    core::{ast::AstNode, document::Document},
+    default::db::tracked::ParsedAst,
+    lsp_types::{CodeAction, Diagnostic, DiagnosticSeverity, Position, Range, TextEdit, Url},
+};
+
+use crate::{
+    ast::{Enum, Error, InterfaceDeclaration, Method, Struct, StructField, Typedef},
+    capabilities::{
+        code_actions::mk_quickfix, diagnostics::Lint, hover::get_doc_lines, suppress::is_directive,
+    },
+    config::Config,
+    util::{children, declarations},
+};
+
+pub const LINT: Lint = Lint {
+    name: "missing-docs",
+    code: "VL0016",
+    opt_in: true,
+};
+pub const FIELD_LINT: Lint = Lint {
+    name: "missing-field-docs",
+    code: "VL0017",
+    opt_in: true,
+};
+
+fn is_documented(document: &Document, node: &dyn AstNode) -> bool {
+    get_doc_lines(&document.texter, node.get_start_position())
+        .unwrap_or_default()
+        .iter()
+        .any(|line| !is_directive(line))
+}
+
+// Fields only have a doc comment if they start on a line of their own
+fn starts_line(document: &Document, node: &dyn AstNode) -> bool {
+    let start = node.get_start_position();
+    document
+        .texter
+        .get_row(start.line as usize)
+        .and_then(|row| row.get(..start.character as usize))
+        .is_some_and(|before| before.trim().is_empty())
+}
+
+fn kind(node: &dyn AstNode) -> &'static str {
+    if node.is::<InterfaceDeclaration>() {
+        "interface"
+    } else if node.is::<Method>() {
+        "method"
+    } else if node.is::<Error>() {
+        "error"
+    } else {
+        "type"
+    }
+}
+
+fn mk_diagnostic(lint: &Lint, kind: &str, name: &dyn AstNode, document: &Document) -> Diagnostic {
+    Diagnostic {
+        range: name.get_lsp_range(document).unwrap(),
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(lint.to_code()),
+        message: format!(
+            "{} `{}` is not documented",
+            kind,
+            name.get_text(document.as_bytes()).unwrap()
+        ),
+        ..Diagnostic::default()
+    }
+}
+
+pub(crate) fn check_documentation(
+    ast: &ParsedAst,
+    document: &Document,
+    config: &Config,
+) -> Vec<Diagnostic> {
+    let mut items = Vec::new();
+    if config.is_enabled(&LINT) {
+        items.extend(
+            declarations(ast)
+                .filter(|(node, _)| !is_documented(document, *node))
+                .map(|(node, name)| mk_diagnostic(&LINT, kind(node), name, document)),
+        );
+    }
+
+    if config.is_enabled(&FIELD_LINT) {
+        items.extend(
+            ast.iter()
+                .filter_map(|node| node.lower().downcast_ref::<StructField>())
+                .filter(|field| starts_line(document, *field) && !is_documented(document, *field))
+                .map(|field| mk_diagnostic(&FIELD_LINT, "field", field.name.cast(ast), document)),
+        );
+    }
+
+    items
+}
+
+fn field_names(ast: &ParsedAst, document: &Document, struct_: &dyn AstNode) -> Vec<String> {
+    let Some(struct_) = struct_.downcast_ref::<Struct>() else {
+        return Vec::new();
+    };
+
+    struct_
+        .member
+        .iter()
+        .map(|member| {
+            let name = member.cast(ast).name.cast(ast);
+            format!("- `{}`:", name.get_text(document.as_bytes()).unwrap())
+        })
+        .collect()
+}
+
+fn section(title: &str, items: Vec<String>) -> Vec<String> {
+    if items.is_empty() {
+        return Vec::new();
+    }
+
+    [String::new(), format!("{}:", title)]
+        .into_iter()
+        .chain(items)
+        .collect()
+}
+
+// A description placeholder followed by the fields to be described
+fn skeleton(ast: &ParsedAst, document: &Document, node: &dyn AstNode, name: &str) -> Vec<String> {
+    let mut lines = vec![format!("TODO: Describe `{}`", name)];
+    let bodies: Vec<&dyn AstNode> = children(ast, node)
+        .filter(|child| child.is::<Struct>() || child.is::<Enum>())
+        .collect();
+
+    if node.is::<Method>() {
+        if let Some(input) = bodies.first() {
+            lines.extend(section("Parameters", field_names(ast, document, *input)));
+        }
+        if let Some(output) = bodies.get(1) {
+            lines.extend(section("Returns", field_names(ast, document, *output)));
+        }
+    } else if node.is::<Error>() {
+        if let Some(parameters) = bodies.first() {
+            lines.extend(section(
+                "Parameters",
+                field_names(ast, document, *parameters),
+            ));
+        }
+    } else if node.is::<Typedef>()
+        && let Some(body) = bodies.first()
+    {
+        lines.extend(section("Fields", field_names(ast, document, *body)));
+    }
+
+    lines
+}
+
+pub(crate) fn insert_doc(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Option<CodeAction> {
+    let node = if LINT.matches(diagnostic) {
+        declarations(ast)
+            .find(|(_, name)| name.get_lsp_range(document).unwrap() == diagnostic.range)
+            .map(|(node, _)| node)?
+    } else if FIELD_LINT.matches(diagnostic) {
+        ast.iter()
+            .filter_map(|node| node.lower().downcast_ref::<StructField>())
+            .find(|field| field.name.cast(ast).get_lsp_range(document).unwrap() == diagnostic.range)
+            .map(|field| field as &dyn AstNode)?
+    } else {
+        return None;
+    };
+
+    let start = node.get_start_position();
+    let indent = document
+        .texter
+        .get_row(start.line as usize)?
+        .get(..start.character as usize)?;
+    let range = diagnostic.range;
+    let name = document
+        .texter
+        .get_row(range.start.line as usize)?
+        .get(range.start.character as usize..range.end.character as usize)?;
+    let position = Position {
+        line: start.line,
+        character: 0,
+    };
+
+    Some(mk_quickfix(
+        uri,
+        diagnostic,
+        format!("Document `{}`", name),
+        vec![TextEdit::new(
+            Range {
+                start: position,
+                end: position,
+            },
+            skeleton(ast, document, node, name)
+                .iter()
+                .map(|line| {
+                    if line.is_empty() {
+                        format!("{}#\n", indent)
+                    } else {
+                        format!("{}# {}\n", indent, line)
+                    }
+                })
+                .collect(),
+        )],
+    ))
+}
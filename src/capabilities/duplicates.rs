@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use auto_lsp::{
+    core::{ast::AstNode, document::Document},
+    default::db::tracked::ParsedAst,
+    lsp_types::{
+        CodeAction, Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location,
+        TextEdit, Url,
+    },
+};
+
+use crate::{
+    ast::{Comment, Enum, Struct, StructField},
+    capabilities::{
+        code_actions::{mk_quickfix, with_rename},
+        diagnostics::Lint,
+        extract_type::{extract, is_anonymous, suggest_type_name},
+    },
+    util::children,
+};
+
+pub const LINT: Lint = Lint {
+    name: "duplicate-shape",
+    code: "VL0015",
+    opt_in: false,
+};
+
+// The structure of the node, i.e. field names and types, with fields and members in any order
+fn shape(ast: &ParsedAst, document_bytes: &[u8], node: &dyn AstNode) -> String {
+    let text = node.get_text(document_bytes).unwrap();
+    let children: Vec<_> = children(ast, node)
+        .filter(|child| !child.is::<Comment>())
+        .collect();
+
+    if let Some(field) = node.downcast_ref::<StructField>() {
+        let name: &dyn AstNode = field.name.cast(ast);
+        let types = children
+            .iter()
+            .filter(|child| !std::ptr::addr_eq(**child, name))
+            .map(|child| shape(ast, document_bytes, *child));
+        return format!(
+            "{}:{}",
+            name.get_text(document_bytes).unwrap(),
+            types.collect::<String>()
+        );
+    }
+
+    if node.is::<Struct>() || node.is::<Enum>() {
+        let mut members: Vec<_> = children
+            .iter()
+            .map(|child| shape(ast, document_bytes, *child))
+            .collect();
+        members.sort();
+        return format!("({})", members.join(","));
+    }
+
+    match children.as_slice() {
+        [] => text.to_string(),
+        // `?`, `[]` and `[string]` followed by the inner type
+        [inner] => {
+            let inner_text = inner.get_text(document_bytes).unwrap();
+            let prefix = text.rfind(inner_text).map_or("", |end| &text[..end]);
+            prefix
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .chain(shape(ast, document_bytes, *inner).chars())
+                .collect()
+        }
+        _ => children
+            .iter()
+            .map(|child| shape(ast, document_bytes, *child))
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+fn size(node: &dyn AstNode) -> usize {
+    if let Some(struct_) = node.downcast_ref::<Struct>() {
+        struct_.member.len()
+    } else if let Some(enum_) = node.downcast_ref::<Enum>() {
+        enum_.member.len()
+    } else {
+        0
+    }
+}
+
+// Anonymous types of at least the given size that occur more than once, in document order
+fn duplicate_groups<'a>(
+    ast: &'a ParsedAst,
+    document: &Document,
+    min_size: usize,
+) -> Vec<Vec<&'a dyn AstNode>> {
+    let mut groups: Vec<Vec<&dyn AstNode>> = Vec::new();
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    ast.iter()
+        .map(|node| node.lower())
+        .filter(|node| is_anonymous(ast, *node) && size(*node) >= min_size)
+        .for_each(|node| {
+            let shape = shape(ast, document.as_bytes(), node);
+            match indices.get(&shape) {
+                Some(index) => groups[*index].push(node),
+                None => {
+                    indices.insert(shape, groups.len());
+                    groups.push(vec![node]);
+                }
+            }
+        });
+
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+fn kind(node: &dyn AstNode) -> &'static str {
+    if node.is::<Enum>() { "enum" } else { "struct" }
+}
+
+pub(crate) fn check_duplicates(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    min_size: usize,
+) -> Vec<Diagnostic> {
+    duplicate_groups(ast, document, min_size)
+        .iter()
+        .flat_map(|group| {
+            group.iter().enumerate().map(move |(i, node)| Diagnostic {
+                range: node.get_lsp_range(document).unwrap(),
+                severity: Some(DiagnosticSeverity::HINT),
+                code: Some(LINT.to_code()),
+                message: format!(
+                    "the same anonymous {} occurs {} times",
+                    kind(*node),
+                    group.len()
+                ),
+                related_information: Some(
+                    group
+                        .iter()
+                        .enumerate()
+                        .filter(|(j, _)| *j != i)
+                        .map(|(_, other)| DiagnosticRelatedInformation {
+                            location: Location {
+                                uri: uri.clone(),
+                                range: other.get_lsp_range(document).unwrap(),
+                            },
+                            message: "also occurs here".into(),
+                        })
+                        .collect(),
+                ),
+                ..Diagnostic::default()
+            })
+        })
+        .collect()
+}
+
+// Replaces all occurences of the duplicated type with a single new declaration
+pub(crate) fn extract_duplicates(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+    min_size: usize,
+) -> Option<CodeAction> {
+    if !LINT.matches(diagnostic) {
+        return None;
+    }
+
+    let group = duplicate_groups(ast, document, min_size)
+        .into_iter()
+        .find(|group| {
+            group
+                .iter()
+                .any(|node| node.get_lsp_range(document).unwrap() == diagnostic.range)
+        })?;
+    let first = *group.first()?;
+    let name = suggest_type_name(ast, document.as_bytes(), first);
+
+    let mut edits = extract(ast, document, first, &name)?;
+    edits.extend(
+        group
+            .iter()
+            .skip(1)
+            .map(|node| TextEdit::new(node.get_lsp_range(document).unwrap(), name.clone())),
+    );
+
+    Some(with_rename(
+        mk_quickfix(
+            uri,
+            diagnostic,
+            format!("Extract {} occurences into type `{}`", group.len(), name),
+            edits,
+        ),
+        uri,
+        first.get_lsp_range(document).unwrap().start,
+    ))
+}
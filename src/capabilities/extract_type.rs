@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use auto_lsp::{
+    core::{ast::AstNode, document::Document},
+    default::db::tracked::ParsedAst,
+    lsp_types::{CodeAction, CodeActionKind, Range, TextEdit, Url},
+};
+
+use crate::{
+    ast::{Enum, Struct, StructField, Typedef},
+    capabilities::{
+        code_actions::{insert_declaration, mk_edit, with_rename},
+        hover::fix_indent,
+        symbols::member_path,
+    },
+    util::{ancestors, indentation, most_specific_at, to_pascal_case},
+};
+
+// Whether the node is a struct or enum that is not the body of a declaration
+pub(crate) fn is_anonymous(ast: &ParsedAst, node: &dyn AstNode) -> bool {
+    (node.is::<Struct>() || node.is::<Enum>())
+        && ancestors(ast, node)
+            .skip(1)
+            .any(|ancestor| ancestor.is::<StructField>())
+}
+
+pub(crate) fn unique_type_name(ast: &ParsedAst, document_bytes: &[u8], name: String) -> String {
+    let existing: HashSet<&str> = ast
+        .iter()
+        .filter_map(|node| node.lower().downcast_ref::<Typedef>())
+        .map(|typedef| typedef.name.cast(ast).get_text(document_bytes).unwrap())
+        .collect();
+
+    if !existing.contains(name.as_str()) {
+        return name;
+    }
+
+    (2..)
+        .map(|i| format!("{}{}", name, i))
+        .find(|candidate| !existing.contains(candidate.as_str()))
+        .unwrap()
+}
+
+// Derives a name from the enclosing declaration and fields, e.g. `CreateUserInput`
+pub(crate) fn suggest_type_name(
+    ast: &ParsedAst,
+    document_bytes: &[u8],
+    node: &dyn AstNode,
+) -> String {
+    let name: String = member_path(ast, document_bytes, node)
+        .unwrap_or_default()
+        .iter()
+        .map(|segment| to_pascal_case(segment))
+        .collect();
+
+    unique_type_name(
+        ast,
+        document_bytes,
+        if name.is_empty() {
+            "NewType".into()
+        } else {
+            name
+        },
+    )
+}
+
+// Moves an anonymous type into a new declaration and refers to that instead
+pub(crate) fn extract(
+    ast: &ParsedAst,
+    document: &Document,
+    node: &dyn AstNode,
+    name: &str,
+) -> Option<Vec<TextEdit>> {
+    let range = node.get_lsp_range(document).unwrap();
+    let level = indentation(document.texter.get_row(range.start.line as usize)?).len();
+    let body = fix_indent(node.get_text(document.as_bytes()).unwrap(), level);
+
+    Some(vec![
+        TextEdit::new(range, name.to_string()),
+        insert_declaration(
+            ast,
+            document,
+            node,
+            &format!("type {} {}", name, body.trim_start()),
+        )?,
+    ])
+}
+
+pub(crate) fn extract_type(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    range: Range,
+) -> Option<CodeAction> {
+    let node = ancestors(ast, most_specific_at(ast, document, range.start)?.lower())
+        .find(|node| is_anonymous(ast, *node))?;
+    let name = suggest_type_name(ast, document.as_bytes(), node);
+
+    let action = CodeAction {
+        title: format!("Extract type `{}`", name),
+        kind: Some(CodeActionKind::REFACTOR_EXTRACT),
+        edit: Some(mk_edit(uri, extract(ast, document, node, &name)?)),
+        ..Default::default()
+    };
+    Some(with_rename(
+        action,
+        uri,
+        node.get_lsp_range(document).unwrap().start,
+    ))
+}
@@ -15,6 +15,7 @@ use crate::{
         InterfaceName, KeywordError, KeywordInterface, KeywordMethod, KeywordType, Method,
         MethodName, Object, StructField, StructFieldName, Typedef, TypedefName, Typeref,
     },
+    capabilities::suppress::is_directive,
     util::{get_file_from_db, leaf_at, walk_up},
 };
 
@@ -23,7 +24,7 @@ fn is_whitespace(c: char) -> bool {
 }
 
 // It would be great to utilize partial formatting for this but this is hard to achieve with Topiary
-fn fix_indent(raw: &str, level: usize) -> String {
+pub(crate) fn fix_indent(raw: &str, level: usize) -> String {
     if raw.lines().skip(1).all(|line| {
         line.get(..level)
             .map(|prefix| prefix.chars().all(is_whitespace))
@@ -39,7 +40,8 @@ fn fix_indent(raw: &str, level: usize) -> String {
     }
 }
 
-fn get_doc(text: &Text, pos: Position) -> anyhow::Result<String> {
+// Finds the `#` comment block right above the given position and at the same column
+pub(crate) fn get_doc_lines(text: &Text, pos: Position) -> anyhow::Result<Vec<&str>> {
     let c = pos.character as usize;
     if !text
         .get_row(pos.line as usize)
@@ -50,10 +52,10 @@ fn get_doc(text: &Text, pos: Position) -> anyhow::Result<String> {
         .take(c)
         .all(is_whitespace)
     {
-        return Ok("".into());
+        return Ok(Vec::new());
     }
 
-    let comments_rev: Vec<&str> = (0..pos.line)
+    let mut comments: Vec<&str> = (0..pos.line)
         .rev()
         .map(|l| text.get_row(l as usize).unwrap())
         .take_while(|line| {
@@ -61,13 +63,22 @@ fn get_doc(text: &Text, pos: Position) -> anyhow::Result<String> {
         })
         .map(|line| &line[c..])
         .collect();
+    comments.reverse();
+
+    Ok(comments)
+}
 
-    if comments_rev.is_empty() {
+fn get_doc(text: &Text, pos: Position) -> anyhow::Result<String> {
+    let comments: Vec<&str> = get_doc_lines(text, pos)?
+        .into_iter()
+        .filter(|line| !is_directive(line))
+        .collect();
+    if comments.is_empty() {
         return Ok("".into());
     }
 
     let mut value = "\n\n---\n".to_string();
-    for line in comments_rev.iter().rev() {
+    for line in comments {
         // TODO: Use `trim_prefix` when available (https://github.com/rust-lang/rust/issues/142312)
         value.push_str(
             line.trim_start_matches("#")
@@ -0,0 +1,535 @@
+use std::collections::HashSet;
+
+use auto_lsp::{
+    anyhow,
+    core::{ast::AstNode, document::Document},
+    default::db::{
+        BaseDatabase,
+        tracked::{ParsedAst, get_ast},
+    },
+    lsp_types::{
+        ApplyWorkspaceEditParams, CodeAction, CodeActionKind, ExecuteCommandParams, MessageType,
+        Position, Range, TextEdit, Url, request::ApplyWorkspaceEdit,
+    },
+    tree_sitter::Parser,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use varlinkfmt_core::{Indent, formatter_tree, mk_language};
+
+use crate::{
+    ast::InterfaceDeclaration,
+    capabilities::{
+        code_actions::{mk_edit, with_rename},
+        diagnostics::{field_name_error, member_name_error},
+    },
+    client,
+    util::{declarations, get_file_from_db, indentation, to_pascal_case, to_snake_case},
+};
+
+pub const INFER_TYPE_COMMAND: &str = "varlink.inferType";
+
+const DEFAULT_NAME: &str = "NewType";
+
+// The grammar only accepts declarations after an interface declaration
+const PLACEHOLDER_INTERFACE: &str = "interface org.example.placeholder";
+
+// What is known about the values of a field from the samples
+#[derive(Clone, Debug, PartialEq)]
+enum Shape {
+    // Only `null` or elements of empty arrays
+    Unknown,
+    Bool,
+    Int,
+    Float,
+    String,
+    Array(Box<Field>),
+    Object(Vec<(String, Field)>),
+    // Samples of different kinds
+    Any,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Field {
+    shape: Shape,
+    // `null` or missing in some samples
+    optional: bool,
+}
+
+impl Field {
+    fn from_value(value: &Value) -> Self {
+        Self {
+            shape: Shape::from_value(value),
+            optional: value.is_null(),
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        Self {
+            shape: self.shape.merge(other.shape),
+            optional: self.optional || other.optional,
+        }
+    }
+
+    fn from_samples<'a>(samples: impl Iterator<Item = &'a Value>) -> Self {
+        samples
+            .map(Self::from_value)
+            .reduce(Self::merge)
+            .unwrap_or(Self {
+                shape: Shape::Unknown,
+                optional: false,
+            })
+    }
+}
+
+impl Shape {
+    fn from_value(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Unknown,
+            Value::Bool(_) => Self::Bool,
+            // Numbers with a fraction or exponent
+            Value::Number(number) if number.is_f64() => Self::Float,
+            Value::Number(_) => Self::Int,
+            Value::String(_) => Self::String,
+            Value::Array(values) => Self::Array(Box::new(Field::from_samples(values.iter()))),
+            Value::Object(fields) => {
+                let mut result: Vec<(String, Field)> = Vec::new();
+                fields.iter().for_each(|(key, value)| {
+                    // Keys without any letter or digit cannot be named
+                    let Some(name) = field_name(key) else {
+                        return;
+                    };
+                    let name = (1..)
+                        .map(|i| match i {
+                            1 => name.clone(),
+                            _ => format!("{}{}", name, i),
+                        })
+                        .find(|candidate| !result.iter().any(|(other, _)| other == candidate))
+                        .unwrap();
+                    result.push((name, Field::from_value(value)));
+                });
+                Self::Object(result)
+            }
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Unknown, shape) | (shape, Self::Unknown) => shape,
+            (Self::Int, Self::Float) | (Self::Float, Self::Int) => Self::Float,
+            (Self::Array(a), Self::Array(b)) => Self::Array(Box::new(a.merge(*b))),
+            (Self::Object(a), Self::Object(mut b)) => {
+                let mut fields: Vec<(String, Field)> = a
+                    .into_iter()
+                    .map(|(name, field)| {
+                        let field = match b.iter().position(|(other, _)| *other == name) {
+                            Some(i) => field.merge(b.remove(i).1),
+                            None => Field {
+                                optional: true,
+                                ..field
+                            },
+                        };
+                        (name, field)
+                    })
+                    .collect();
+                fields.extend(b.into_iter().map(|(name, field)| {
+                    (
+                        name,
+                        Field {
+                            optional: true,
+                            ..field
+                        },
+                    )
+                }));
+                Self::Object(fields)
+            }
+            (a, b) if a == b => a,
+            _ => Self::Any,
+        }
+    }
+}
+
+// Turns a JSON key into a valid field name, e.g. `first-name` into `first_name`
+fn field_name(key: &str) -> Option<String> {
+    let name = to_snake_case(key)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    let name = match name.chars().next()? {
+        c if c.is_ascii_digit() => format!("field_{}", name),
+        _ => name,
+    };
+    field_name_error("field", &name).is_none().then_some(name)
+}
+
+// Derives a type name from a field name, e.g. `user_info` into `UserInfo`
+fn type_name(hint: &str) -> String {
+    let name = to_pascal_case(hint);
+    match member_name_error("type", &name) {
+        None => name,
+        Some(_) => DEFAULT_NAME.into(),
+    }
+}
+
+struct Renderer<'a> {
+    unit: &'a str,
+    // Nested objects become declarations of their own unless inlined
+    inline: bool,
+    declarations: Vec<String>,
+    taken: HashSet<String>,
+}
+
+impl Renderer<'_> {
+    fn field(&mut self, field: &Field, hint: &str, indent: &str) -> String {
+        let type_ = self.shape(&field.shape, hint, indent);
+        if field.optional {
+            format!("?{}", type_)
+        } else {
+            type_
+        }
+    }
+
+    fn shape(&mut self, shape: &Shape, hint: &str, indent: &str) -> String {
+        match shape {
+            Shape::Unknown | Shape::Any => "any".into(),
+            Shape::Bool => "bool".into(),
+            Shape::Int => "int".into(),
+            Shape::Float => "float".into(),
+            Shape::String => "string".into(),
+            Shape::Array(element) => format!("[]{}", self.field(element, hint, indent)),
+            Shape::Object(fields) if self.inline => self.struct_(fields, indent),
+            Shape::Object(fields) => self.declare(&type_name(hint), fields),
+        }
+    }
+
+    fn struct_(&mut self, fields: &[(String, Field)], indent: &str) -> String {
+        if fields.is_empty() {
+            return "()".into();
+        }
+
+        let inner = format!("{}{}", indent, self.unit);
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(name, field)| format!("{}{}: {}", inner, name, self.field(field, name, &inner)))
+            .collect();
+        format!("(\n{}\n{})", fields.join(",\n"), indent)
+    }
+
+    fn declare(&mut self, name: &str, fields: &[(String, Field)]) -> String {
+        let name = (1..)
+            .map(|i| match i {
+                1 => name.to_string(),
+                _ => format!("{}{}", name, i),
+            })
+            .find(|candidate| !self.taken.contains(candidate))
+            .unwrap();
+        self.taken.insert(name.clone());
+
+        // Reserve the position so that declarations precede the ones they refer to
+        let index = self.declarations.len();
+        self.declarations.push(String::new());
+        let body = self.struct_(fields, "");
+        self.declarations[index] = format!("type {} {}", name, body);
+        name
+    }
+}
+
+// The indentation used by the document, e.g. `    `
+pub(crate) fn indent_unit(document: &Document) -> String {
+    document
+        .texter
+        .text
+        .lines()
+        .map(indentation)
+        .find(|indent| !indent.is_empty())
+        .unwrap_or("  ")
+        .to_string()
+}
+
+// Derives a type from a JSON value, e.g. `(id: int, tags: []string)`
+pub(crate) fn infer(value: &Value, indent: &str, unit: &str) -> String {
+    Renderer {
+        unit,
+        inline: true,
+        declarations: Vec::new(),
+        taken: HashSet::new(),
+    }
+    .field(&Field::from_value(value), "", indent)
+}
+
+// Derives type declarations from a JSON object or an array of sample objects, left unindented
+// for the formatter
+fn infer_declarations(value: &Value, name: &str, taken: HashSet<String>) -> Option<String> {
+    let field = match value {
+        Value::Array(samples) => Field::from_samples(samples.iter()),
+        _ => Field::from_value(value),
+    };
+    let Shape::Object(fields) = field.shape else {
+        return None;
+    };
+
+    let mut renderer = Renderer {
+        unit: "",
+        inline: false,
+        declarations: Vec::new(),
+        taken,
+    };
+    renderer.declare(name, &fields);
+    Some(renderer.declarations.join("\n\n"))
+}
+
+// The indentation used by the document, for the formatter
+fn indent(document: &Document) -> Indent {
+    let unit = indent_unit(document);
+    if unit.starts_with('\t') {
+        Indent::Tab
+    } else {
+        Indent::Spaces(unit.len())
+    }
+}
+
+// Formats declarations the way the formatting request does
+fn format_declarations(declarations: &str, indent: Indent) -> Option<String> {
+    let text = format!("{}\n\n{}\n", PLACEHOLDER_INTERFACE, declarations);
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_varlink::LANGUAGE.into())
+        .ok()?;
+    let tree = parser.parse(&text, None)?;
+
+    let mut output = Vec::new();
+    formatter_tree(
+        tree.into(),
+        &text,
+        &mut output,
+        &mk_language(indent),
+        Default::default(),
+    )
+    .ok()?;
+
+    Some(
+        String::from_utf8(output)
+            .ok()?
+            .strip_prefix(PLACEHOLDER_INTERFACE)?
+            .trim_start_matches('\n')
+            .to_string(),
+    )
+}
+
+// Finds the first JSON document in the text, e.g. a sample in a doc comment
+pub(crate) fn find_sample(text: &str) -> Option<Value> {
+    text.match_indices(['{', '[']).find_map(|(start, _)| {
+        serde_json::Deserializer::from_str(&text[start..])
+            .into_iter::<Value>()
+            .next()?
+            .ok()
+    })
+}
+
+// Names which the generated declarations must not use
+fn declared_names(ast: &ParsedAst, document_bytes: &[u8]) -> HashSet<String> {
+    declarations(ast)
+        .filter(|(node, _)| !node.is::<InterfaceDeclaration>())
+        .map(|(_, name)| name.get_text(document_bytes).unwrap().to_string())
+        .collect()
+}
+
+fn offset(text: &str, position: Position) -> Option<usize> {
+    let mut lines = text.split_inclusive('\n');
+    let before: usize = lines
+        .by_ref()
+        .take(position.line as usize)
+        .map(str::len)
+        .sum();
+    Some(before + position.character as usize).filter(|offset| *offset <= text.len())
+}
+
+fn insert_type_edit(
+    ast: &ParsedAst,
+    document: &Document,
+    range: Range,
+    sample: &Value,
+    name: &str,
+) -> Option<TextEdit> {
+    let declarations = infer_declarations(sample, name, declared_names(ast, document.as_bytes()))?;
+    Some(TextEdit::new(
+        range,
+        format_declarations(&declarations, indent(document))?,
+    ))
+}
+
+// Replaces a JSON sample pasted into the document with the type inferred from it
+pub(crate) fn infer_type(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    range: Range,
+) -> Option<CodeAction> {
+    let text = &document.texter.text;
+    let selected = text.get(offset(text, range.start)?..offset(text, range.end)?)?;
+    let sample: Value = serde_json::from_str(selected.trim()).ok()?;
+    let edit = insert_type_edit(ast, document, range, &sample, DEFAULT_NAME)?;
+
+    let action = CodeAction {
+        title: "Infer type from JSON sample".into(),
+        kind: Some(CodeActionKind::REFACTOR_REWRITE),
+        edit: Some(mk_edit(uri, vec![edit])),
+        ..Default::default()
+    };
+    // The declaration of the new type comes first
+    let name_position = Position {
+        line: range.start.line,
+        character: range.start.character + "type ".len() as u32,
+    };
+    Some(with_rename(action, uri, name_position))
+}
+
+#[derive(Deserialize)]
+struct InferTypeArguments {
+    uri: Url,
+    position: Position,
+    // A JSON document, either a sample object or an array of sample objects
+    json: String,
+    name: Option<String>,
+}
+
+pub fn execute_command(
+    db: &impl BaseDatabase,
+    params: ExecuteCommandParams,
+) -> anyhow::Result<Option<Value>> {
+    if params.command != INFER_TYPE_COMMAND {
+        return Err(anyhow::format_err!("Unknown command: {}", params.command));
+    }
+
+    let arguments: InferTypeArguments = serde_json::from_value(
+        params
+            .arguments
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::format_err!("Missing arguments"))?,
+    )?;
+    let sample: Value = serde_json::from_str(&arguments.json)?;
+    let name = arguments.name.as_deref().unwrap_or(DEFAULT_NAME);
+    if let Some(error) = member_name_error("type name", name) {
+        return Err(anyhow::format_err!(error));
+    }
+
+    let file = get_file_from_db(&arguments.uri, db)?;
+    let ast = get_ast(db, file);
+    let document = file.document(db);
+    let edit = insert_type_edit(
+        ast,
+        document,
+        Range {
+            start: arguments.position,
+            end: arguments.position,
+        },
+        &sample,
+        name,
+    )
+    .ok_or_else(|| anyhow::format_err!("JSON sample must be an object or an array of objects"))?;
+
+    client::send_request::<ApplyWorkspaceEdit>(
+        ApplyWorkspaceEditParams {
+            label: Some("Infer type from JSON sample".into()),
+            edit: mk_edit(&arguments.uri, vec![edit]),
+        },
+        |response| {
+            if !response.applied {
+                client::show_message(
+                    MessageType::ERROR,
+                    format!(
+                        "Could not insert the inferred type: {}",
+                        response
+                            .failure_reason
+                            .unwrap_or_else(|| "rejected by the editor".into())
+                    ),
+                );
+            }
+        },
+    )?;
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(Shape::from_value(&json!(1)), Shape::Int);
+        assert_eq!(Shape::from_value(&json!(-1)), Shape::Int);
+        assert_eq!(Shape::from_value(&json!(1.5)), Shape::Float);
+        assert_eq!(Shape::Int.merge(Shape::Float), Shape::Float);
+        assert_eq!(infer(&json!([1, 2.5]), "", "  "), "[]float");
+        assert_eq!(infer(&json!([1, "a"]), "", "  "), "[]any");
+    }
+
+    #[test]
+    fn optional_fields() {
+        let samples = json!([
+            {"a": 1, "b": null, "c": "x"},
+            {"a": 2, "b": "y"},
+        ]);
+        assert_eq!(
+            infer_declarations(&samples, "Foo", HashSet::new()).unwrap(),
+            "type Foo (\na: int,\nb: ?string,\nc: ?string\n)"
+        );
+        assert_eq!(infer(&json!({"a": null}), "", "  "), "(\n  a: ?any\n)");
+    }
+
+    #[test]
+    fn nested_objects() {
+        let value = json!({"id": 1, "owner": {"name": "x"}, "tags": ["a"]});
+        assert_eq!(
+            infer_declarations(&value, "Foo", HashSet::new()).unwrap(),
+            "type Foo (\nid: int,\nowner: Owner,\ntags: []string\n)\n\ntype Owner (\nname: string\n)"
+        );
+        assert_eq!(
+            infer(&value, "", "  "),
+            "(\n  id: int,\n  owner: (\n    name: string\n  ),\n  tags: []string\n)"
+        );
+        assert_eq!(
+            infer_declarations(&value, "Foo", HashSet::from(["Owner".to_string()])).unwrap(),
+            "type Foo (\nid: int,\nowner: Owner2,\ntags: []string\n)\n\ntype Owner2 (\nname: string\n)"
+        );
+        assert_eq!(
+            infer_declarations(&json!([1, 2]), "Foo", HashSet::new()),
+            None
+        );
+    }
+
+    #[test]
+    fn arrays() {
+        assert_eq!(infer(&json!([]), "", "  "), "[]any");
+        assert_eq!(infer(&json!([[1], []]), "", "  "), "[][]int");
+        assert_eq!(
+            infer(&json!([{"a": 1}, {"b": true}]), "", "  "),
+            "[](\n  a: ?int,\n  b: ?bool\n)"
+        );
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(field_name("first-name").as_deref(), Some("first_name"));
+        assert_eq!(field_name("firstName").as_deref(), Some("first_name"));
+        assert_eq!(field_name("2fa").as_deref(), Some("field_2fa"));
+        assert_eq!(field_name("a.b c").as_deref(), Some("a_b_c"));
+        assert_eq!(field_name("$$"), None);
+        assert_eq!(type_name("user_info"), "UserInfo");
+        assert_eq!(type_name(""), DEFAULT_NAME);
+
+        let Shape::Object(fields) = Shape::from_value(&json!({"a-b": 1, "a_b": 2})) else {
+            unreachable!();
+        };
+        let names: Vec<_> = fields.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["a_b", "a_b2"]);
+    }
+}
@@ -0,0 +1,108 @@
+use auto_lsp::{
+    core::{ast::AstNode, document::Document},
+    default::db::tracked::ParsedAst,
+    lsp_types::{CodeAction, CodeActionKind, Range, TextEdit, Url},
+};
+
+use crate::{
+    ast::{Enum, Struct, Typedef, TypedefName, Typeref},
+    capabilities::{
+        code_actions::{mk_edit, removal_range},
+        hover::fix_indent,
+    },
+    util::{children, indentation, is_recursive, leaf_at, type_dependencies, walk_up},
+};
+
+pub(crate) fn inline_type(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    range: Range,
+) -> Vec<CodeAction> {
+    let document_bytes = document.as_bytes();
+    let Some(leaf) = leaf_at(ast, document, range.start) else {
+        return Vec::new();
+    };
+    let leaf = leaf.lower();
+
+    let name = if let Some(typedef_name) = walk_up::<TypedefName>(ast, leaf) {
+        typedef_name.get_text(document_bytes).unwrap()
+    } else if let Some(typeref) = walk_up::<Typeref>(ast, leaf) {
+        typeref.get_text(document_bytes).unwrap()
+    } else {
+        return Vec::new();
+    };
+
+    let typedefs: Vec<&Typedef> = ast
+        .iter()
+        .filter_map(|node| node.lower().downcast_ref::<Typedef>())
+        .filter(|typedef| typedef.name.cast(ast).get_text(document_bytes).unwrap() == name)
+        .collect();
+    let [typedef] = typedefs[..] else {
+        return Vec::new();
+    };
+
+    // Inlining would never terminate
+    if is_recursive(
+        &type_dependencies(ast, document_bytes),
+        document_bytes,
+        name,
+    ) {
+        return Vec::new();
+    }
+
+    let Some(body) = children(ast, typedef).find(|node| node.is::<Struct>() || node.is::<Enum>())
+    else {
+        return Vec::new();
+    };
+    let Some(level) = document
+        .texter
+        .get_row(typedef.get_lsp_range(document).unwrap().start.line as usize)
+        .map(indentation)
+    else {
+        return Vec::new();
+    };
+    let body = fix_indent(body.get_text(document_bytes).unwrap(), level.len());
+
+    let Some(edits) = ast
+        .iter()
+        .filter_map(|node| node.lower().downcast_ref::<Typeref>())
+        .filter(|typeref| typeref.get_text(document_bytes).unwrap() == name)
+        .map(|typeref| {
+            let range = typeref.get_lsp_range(document).unwrap();
+            let indent = indentation(document.texter.get_row(range.start.line as usize)?);
+            Some(TextEdit::new(
+                range,
+                body.trim_start().replace('\n', &format!("\n{}", indent)),
+            ))
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Vec::new();
+    };
+
+    if edits.is_empty() {
+        return Vec::new();
+    }
+
+    let mut actions = vec![CodeAction {
+        title: format!("Inline type `{}`", name),
+        kind: Some(CodeActionKind::REFACTOR_INLINE),
+        edit: Some(mk_edit(uri, edits.clone())),
+        ..Default::default()
+    }];
+
+    if let Some(removal) = removal_range(document, typedef.get_lsp_range(document).unwrap()) {
+        actions.push(CodeAction {
+            title: format!("Inline type `{}` and remove its definition", name),
+            kind: Some(CodeActionKind::REFACTOR_INLINE),
+            edit: Some(mk_edit(
+                uri,
+                [edits, vec![TextEdit::new(removal, String::new())]].concat(),
+            )),
+            ..Default::default()
+        });
+    }
+
+    actions
+}
@@ -0,0 +1,107 @@
+use auto_lsp::{
+    core::{ast::AstNode, document::Document},
+    default::db::tracked::ParsedAst,
+    lsp_types::{
+        CodeAction, CodeDescription, Diagnostic, DiagnosticSeverity, Range, TextEdit, Url,
+    },
+};
+use serde_json::Value;
+
+use crate::{
+    ast::{Any, Object, StructField},
+    capabilities::{
+        code_actions::mk_quickfix,
+        diagnostics::Lint,
+        hover::get_doc_lines,
+        infer_type::{find_sample, indent_unit, infer},
+    },
+    util::{capture_at, indentation, walk_up},
+};
+
+pub const LINT: Lint = Lint {
+    name: "loose-type",
+    code: "VL0019",
+    opt_in: true,
+};
+
+const EXPLANATION: &str =
+    "https://github.com/bachorp/varlink-language-server#loosely-typed-object-and-any";
+
+pub(crate) fn check_loose_types(ast: &ParsedAst, document: &Document) -> Vec<Diagnostic> {
+    ast.iter()
+        .filter_map(|node| {
+            let node = node.lower();
+            let message = if node.is::<Object>() {
+                "`object` accepts any JSON object, consider a struct"
+            } else if node.is::<Any>() {
+                "`any` accepts any JSON value, consider a concrete type"
+            } else {
+                return None;
+            };
+
+            Some(Diagnostic {
+                range: node.get_lsp_range(document).unwrap(),
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(LINT.to_code()),
+                code_description: Some(CodeDescription {
+                    href: Url::parse(EXPLANATION).unwrap(),
+                }),
+                message: message.into(),
+                ..Diagnostic::default()
+            })
+        })
+        .collect()
+}
+
+fn loose_type_at<'a>(
+    ast: &'a ParsedAst,
+    document: &Document,
+    range: Range,
+) -> Option<&'a dyn AstNode> {
+    let node: &dyn AstNode = match capture_at::<Object>(ast, document, range.start) {
+        Some(object) => object,
+        None => capture_at::<Any>(ast, document, range.start)?,
+    };
+    (node.get_lsp_range(document).unwrap() == range).then_some(node)
+}
+
+// Replaces the loose type with one inferred from a JSON sample in the doc comment of the field
+pub(crate) fn infer_from_sample(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Option<CodeAction> {
+    if !LINT.matches(diagnostic) {
+        return None;
+    }
+
+    let node = loose_type_at(ast, document, diagnostic.range)?;
+    let field = walk_up::<StructField>(ast, node)?;
+    let doc = get_doc_lines(&document.texter, field.get_start_position()).ok()?;
+    let sample = find_sample(
+        &doc.iter()
+            .map(|line| line.trim_start_matches('#'))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )?;
+    if node.is::<Object>() && !matches!(sample, Value::Object(_)) {
+        return None;
+    }
+
+    let indent = indentation(
+        document
+            .texter
+            .get_row(diagnostic.range.start.line as usize)?,
+    );
+
+    Some(mk_quickfix(
+        uri,
+        diagnostic,
+        "Infer type from JSON sample".into(),
+        vec![TextEdit::new(
+            diagnostic.range,
+            infer(&sample, &indent, &indent_unit(document)),
+        )],
+    ))
+}
@@ -1,12 +1,25 @@
+pub mod code_actions;
 pub mod completion;
+pub mod complexity;
+pub mod dangling_docs;
 pub mod diagnostics;
+pub mod documentation;
+pub mod duplicates;
+pub mod extract_type;
 pub mod symbols;
 pub mod folding_range;
 pub mod formatting;
 pub mod goto_definition;
 pub mod highlight;
 pub mod hover;
+pub mod infer_type;
+pub mod inline_type;
+pub mod loose_types;
+pub mod naming;
+pub mod organize;
 pub mod references;
 pub mod rename;
 pub mod selection_range;
 pub mod semantic_tokens;
+pub mod suppress;
+pub mod type_modifiers;
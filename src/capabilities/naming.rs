@@ -0,0 +1,310 @@
+use auto_lsp::{
+    core::{ast::AstNode, document::Document},
+    default::db::tracked::ParsedAst,
+    lsp_types::{Diagnostic, DiagnosticSeverity, Range},
+};
+
+use crate::{
+    ast::{EnumMemberName, Error, InterfaceDeclaration, Method, StructField, Typedef},
+    capabilities::diagnostics::{Lint, field_name_error, interface_name_error, member_name_error},
+    config::Config,
+    util::{to_pascal_case, to_snake_case},
+};
+
+pub const INTERFACE_NAME: Lint = Lint {
+    name: "interface-name",
+    code: "VL0101",
+    opt_in: true,
+};
+pub const TYPE_NAME: Lint = Lint {
+    name: "type-name",
+    code: "VL0102",
+    opt_in: true,
+};
+pub const METHOD_NAME: Lint = Lint {
+    name: "method-name",
+    code: "VL0103",
+    opt_in: true,
+};
+pub const ERROR_NAME: Lint = Lint {
+    name: "error-name",
+    code: "VL0104",
+    opt_in: true,
+};
+pub const FIELD_NAME: Lint = Lint {
+    name: "field-name",
+    code: "VL0105",
+    opt_in: true,
+};
+pub const ENUM_MEMBER_NAME: Lint = Lint {
+    name: "enum-member-name",
+    code: "VL0106",
+    opt_in: true,
+};
+
+pub const LINTS: &[Lint] = &[
+    INTERFACE_NAME,
+    TYPE_NAME,
+    METHOD_NAME,
+    ERROR_NAME,
+    FIELD_NAME,
+    ENUM_MEMBER_NAME,
+];
+
+// Lowercase reverse-domain, e.g. `org.example.foo`
+fn is_interface_name(name: &str) -> bool {
+    name.contains('.') && !name.chars().any(|c| c.is_uppercase() || c == '_')
+}
+
+// Writes acronyms as words, e.g. `HTTPServer` as `HttpServer`
+fn lower_acronyms(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let after_uppercase = i > 0 && chars[i - 1].is_ascii_uppercase();
+            let before_lowercase = chars
+                .get(i + 1)
+                .is_some_and(|next| next.is_ascii_lowercase());
+            if c.is_ascii_uppercase() && after_uppercase && !before_lowercase {
+                c.to_ascii_lowercase()
+            } else {
+                *c
+            }
+        })
+        .collect()
+}
+
+fn is_pascal_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_alphanumeric())
+        && lower_acronyms(name) == name
+}
+
+fn is_snake_or_camel_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && (name.chars().all(|c| c.is_alphanumeric())
+            || name
+                .chars()
+                .all(|c| c.is_lowercase() || c.is_ascii_digit() || c == '_'))
+}
+
+// Whether the name is rejected by the specification, which is reported as `invalid-name` instead
+fn violates_specification(lint: &Lint, name: &str) -> bool {
+    match *lint {
+        INTERFACE_NAME => interface_name_error(name).is_some(),
+        TYPE_NAME | METHOD_NAME | ERROR_NAME => member_name_error("name", name).is_some(),
+        _ => field_name_error("name", name).is_some(),
+    }
+}
+
+fn follows_convention(lint: &Lint, name: &str) -> bool {
+    match *lint {
+        INTERFACE_NAME => is_interface_name(name),
+        TYPE_NAME | METHOD_NAME | ERROR_NAME => is_pascal_case(name),
+        _ => is_snake_or_camel_case(name),
+    }
+}
+
+fn is_reported(lint: &Lint, name: &str) -> bool {
+    !name.is_empty() && !follows_convention(lint, name) && !violates_specification(lint, name)
+}
+
+// The name following the convention that the given lint enforces
+pub fn suggest_name(lint: &Lint, name: &str) -> Option<String> {
+    let suggestion = match *lint {
+        INTERFACE_NAME => name.to_lowercase().replace('_', "-"),
+        TYPE_NAME | METHOD_NAME | ERROR_NAME => lower_acronyms(&to_pascal_case(name)),
+        FIELD_NAME | ENUM_MEMBER_NAME => to_snake_case(name),
+        _ => return None,
+    };
+
+    (follows_convention(lint, &suggestion)
+        && !violates_specification(lint, &suggestion)
+        && suggestion != name)
+        .then_some(suggestion)
+}
+
+// The lint for the kind of name at the given range, together with the name
+pub(crate) fn name_at<'a>(
+    ast: &'a ParsedAst,
+    document: &'a Document,
+    range: Range,
+) -> Option<(&'static Lint, &'a str)> {
+    ast.iter().find_map(|node| {
+        let node = node.lower();
+        let (lint, name): (&'static Lint, &dyn AstNode) =
+            if let Some(interface) = node.downcast_ref::<InterfaceDeclaration>() {
+                (&INTERFACE_NAME, interface.name.cast(ast))
+            } else if let Some(typedef) = node.downcast_ref::<Typedef>() {
+                (&TYPE_NAME, typedef.name.cast(ast))
+            } else if let Some(method) = node.downcast_ref::<Method>() {
+                (&METHOD_NAME, method.name.cast(ast))
+            } else if let Some(error) = node.downcast_ref::<Error>() {
+                (&ERROR_NAME, error.name.cast(ast))
+            } else if let Some(field) = node.downcast_ref::<StructField>() {
+                (&FIELD_NAME, field.name.cast(ast))
+            } else if let Some(member) = node.downcast_ref::<EnumMemberName>() {
+                (&ENUM_MEMBER_NAME, member)
+            } else {
+                return None;
+            };
+
+        (name.get_lsp_range(document).unwrap() == range)
+            .then(|| (lint, name.get_text(document.as_bytes()).unwrap()))
+    })
+}
+
+fn check(
+    lint: &Lint,
+    kind: &str,
+    convention: &str,
+    name: &dyn AstNode,
+    document: &Document,
+) -> Option<Diagnostic> {
+    let text = name.get_text(document.as_bytes()).ok()?;
+    if !is_reported(lint, text) {
+        return None;
+    }
+
+    Some(Diagnostic {
+        range: name.get_lsp_range(document).unwrap(),
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(lint.to_code()),
+        message: format!("{} `{}` should be {}", kind, text, convention),
+        ..Diagnostic::default()
+    })
+}
+
+pub(crate) fn check_naming(
+    ast: &ParsedAst,
+    document: &Document,
+    config: &Config,
+) -> Vec<Diagnostic> {
+    let enabled = |lint: &Lint| config.is_enabled(lint);
+
+    ast.iter()
+        .filter_map(|node| {
+            let node = node.lower();
+            if let Some(interface) = node.downcast_ref::<InterfaceDeclaration>()
+                && enabled(&INTERFACE_NAME)
+            {
+                check(
+                    &INTERFACE_NAME,
+                    "interface name",
+                    "lowercase reverse-domain",
+                    interface.name.cast(ast),
+                    document,
+                )
+            } else if let Some(typedef) = node.downcast_ref::<Typedef>()
+                && enabled(&TYPE_NAME)
+            {
+                check(
+                    &TYPE_NAME,
+                    "type",
+                    "PascalCase",
+                    typedef.name.cast(ast),
+                    document,
+                )
+            } else if let Some(method) = node.downcast_ref::<Method>()
+                && enabled(&METHOD_NAME)
+            {
+                check(
+                    &METHOD_NAME,
+                    "method",
+                    "PascalCase",
+                    method.name.cast(ast),
+                    document,
+                )
+            } else if let Some(error) = node.downcast_ref::<Error>()
+                && enabled(&ERROR_NAME)
+            {
+                check(
+                    &ERROR_NAME,
+                    "error",
+                    "PascalCase",
+                    error.name.cast(ast),
+                    document,
+                )
+            } else if let Some(field) = node.downcast_ref::<StructField>()
+                && enabled(&FIELD_NAME)
+            {
+                check(
+                    &FIELD_NAME,
+                    "struct field",
+                    "snake_case or camelCase",
+                    field.name.cast(ast),
+                    document,
+                )
+            } else if let Some(member) = node.downcast_ref::<EnumMemberName>()
+                && enabled(&ENUM_MEMBER_NAME)
+            {
+                check(
+                    &ENUM_MEMBER_NAME,
+                    "enum member",
+                    "snake_case or camelCase",
+                    member,
+                    document,
+                )
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reported_names() {
+        for (lint, name) in [
+            (INTERFACE_NAME, "org.Example.foo"),
+            (TYPE_NAME, "HTTPServer"),
+            (METHOD_NAME, "GETUser"),
+            (ERROR_NAME, "NOTFOUND"),
+            (FIELD_NAME, "FooBar"),
+            (ENUM_MEMBER_NAME, "foo_Bar"),
+        ] {
+            assert!(is_reported(&lint, name), "{}: {}", lint.name, name);
+        }
+
+        for (lint, name) in [
+            (INTERFACE_NAME, "org.example.foo"),
+            (TYPE_NAME, "HttpServer"),
+            (TYPE_NAME, "Http2Server"),
+            (ERROR_NAME, "A"),
+            (FIELD_NAME, "fooBar"),
+            (ENUM_MEMBER_NAME, "foo_bar"),
+            // Left to `invalid-name`
+            (TYPE_NAME, "httpServer"),
+            (FIELD_NAME, "foo__bar"),
+        ] {
+            assert!(!is_reported(&lint, name), "{}: {}", lint.name, name);
+        }
+    }
+
+    #[test]
+    fn suggestions() {
+        assert_eq!(
+            suggest_name(&INTERFACE_NAME, "org.Example.foo").as_deref(),
+            Some("org.example.foo")
+        );
+        assert_eq!(
+            suggest_name(&TYPE_NAME, "HTTPServer").as_deref(),
+            Some("HttpServer")
+        );
+        assert_eq!(
+            suggest_name(&ERROR_NAME, "IOError").as_deref(),
+            Some("IoError")
+        );
+        assert_eq!(suggest_name(&METHOD_NAME, "FOO").as_deref(), Some("Foo"));
+        assert_eq!(
+            suggest_name(&FIELD_NAME, "FooBar").as_deref(),
+            Some("foo_bar")
+        );
+        assert_eq!(suggest_name(&TYPE_NAME, "HttpServer"), None);
+    }
+}
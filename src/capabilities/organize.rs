@@ -0,0 +1,232 @@
+use auto_lsp::{
+    core::{ast::AstNode, document::Document},
+    default::db::tracked::ParsedAst,
+    lsp_types::{
+        CodeAction, CodeActionKind, Diagnostic, DiagnosticSeverity, Position, Range, TextEdit, Url,
+    },
+};
+
+use crate::{
+    ast::{Error, Interface, Method, Typedef},
+    capabilities::{code_actions::mk_edit, diagnostics::Lint, hover::get_doc_lines},
+    config::{self, DeclarationKind},
+};
+
+pub const LINT: Lint = Lint {
+    name: "unorganized-declarations",
+    code: "VL0007",
+    opt_in: true,
+};
+
+// A top-level declaration together with its doc comment
+struct Block<'a> {
+    kind: DeclarationKind,
+    name: &'a str,
+    name_range: Range,
+    start_line: u32,
+    end_line: u32,
+}
+
+pub(crate) struct Organized {
+    pub range: Range,
+    pub text: String,
+    // The name of the first declaration that is out of place
+    pub misplaced: Range,
+}
+
+fn row<'a>(document: &'a Document, line: u32) -> Option<&'a str> {
+    Some(
+        document
+            .texter
+            .get_row(line as usize)?
+            .trim_end_matches(['\r', '\n']),
+    )
+}
+
+fn mk_block<'a>(
+    ast: &'a ParsedAst,
+    document: &'a Document,
+    node: &'a dyn AstNode,
+) -> Option<Block<'a>> {
+    let document_bytes = document.as_bytes();
+    let (kind, name) = if let Some(typedef) = node.downcast_ref::<Typedef>() {
+        (
+            DeclarationKind::Type,
+            typedef.name.cast(ast) as &dyn AstNode,
+        )
+    } else if let Some(method) = node.downcast_ref::<Method>() {
+        (
+            DeclarationKind::Method,
+            method.name.cast(ast) as &dyn AstNode,
+        )
+    } else if let Some(error) = node.downcast_ref::<Error>() {
+        (DeclarationKind::Error, error.name.cast(ast) as &dyn AstNode)
+    } else {
+        return None;
+    };
+
+    let range = node.get_lsp_range(document).unwrap();
+    Some(Block {
+        kind,
+        name: name.get_text(document_bytes).unwrap(),
+        name_range: name.get_lsp_range(document).unwrap(),
+        start_line: range.start.line
+            - get_doc_lines(&document.texter, range.start).ok()?.len() as u32,
+        end_line: range.end.line,
+    })
+}
+
+// Sorts the top-level declarations by kind and name. Nothing is returned if they are already
+// organized.
+pub(crate) fn organize(
+    ast: &ParsedAst,
+    document: &Document,
+    order: &[DeclarationKind],
+) -> Option<Organized> {
+    let mut blocks = Vec::new();
+    for node in ast.iter() {
+        let node = node.lower();
+        if !node
+            .get_parent(ast)
+            .is_some_and(|parent| parent.is::<Interface>())
+        {
+            continue;
+        }
+
+        let Some(block) = mk_block(ast, document, node) else {
+            continue;
+        };
+
+        // Each declaration must span whole lines
+        let range = node.get_lsp_range(document).unwrap();
+        if !row(document, range.start.line)?
+            .get(..range.start.character as usize)?
+            .trim()
+            .is_empty()
+            || !row(document, range.end.line)?
+                .get(range.end.character as usize..)?
+                .trim()
+                .is_empty()
+        {
+            return None;
+        }
+
+        blocks.push(block);
+    }
+
+    // Everything between two declarations, e.g. comments, moves together with the latter
+    let mut leading = vec![0];
+    for pair in blocks.windows(2) {
+        if pair[1].start_line <= pair[0].end_line {
+            return None;
+        }
+
+        leading.push(pair[1].start_line - (pair[0].end_line + 1));
+    }
+
+    let rank = |block: &Block| {
+        order
+            .iter()
+            .position(|kind| *kind == block.kind)
+            .unwrap_or(order.len())
+    };
+    let mut sorted: Vec<usize> = (0..blocks.len()).collect();
+    sorted.sort_by_key(|i| {
+        (
+            rank(&blocks[*i]),
+            blocks[*i].name.to_lowercase(),
+            blocks[*i].name,
+        )
+    });
+
+    let misplaced = blocks[sorted
+        .iter()
+        .enumerate()
+        .position(|(position, i)| position != *i)?]
+    .name_range;
+
+    // The lines of each declaration, starting with its leading blank lines
+    let mut chunks = Vec::new();
+    for (block, leading) in blocks.iter().zip(leading) {
+        let lines = (block.start_line - leading..=block.end_line)
+            .map(|line| row(document, line))
+            .collect::<Option<Vec<_>>>()?;
+        let blank = lines
+            .iter()
+            .take_while(|line| line.trim().is_empty())
+            .count();
+        chunks.push((blank, lines[blank..].join("\n")));
+    }
+
+    // The declaration which moves to the top trades its blank lines with the former first one
+    chunks[0].0 = chunks[sorted[0]].0;
+    chunks[sorted[0]].0 = 0;
+
+    let text = sorted
+        .iter()
+        .map(|i| {
+            let (blank, lines) = &chunks[*i];
+            "\n".repeat(*blank) + lines
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let (first, last) = (blocks.first()?, blocks.last()?);
+    Some(Organized {
+        range: Range {
+            start: Position {
+                line: first.start_line,
+                character: 0,
+            },
+            end: Position {
+                line: last.end_line,
+                character: row(document, last.end_line)?.len() as u32,
+            },
+        },
+        text,
+        misplaced,
+    })
+}
+
+pub(crate) fn check_organized(
+    ast: &ParsedAst,
+    document: &Document,
+    order: &[DeclarationKind],
+) -> Vec<Diagnostic> {
+    organize(ast, document, order)
+        .map(|organized| Diagnostic {
+            range: organized.misplaced,
+            severity: Some(DiagnosticSeverity::HINT),
+            code: Some(LINT.to_code()),
+            message: "declarations are not organized".into(),
+            ..Diagnostic::default()
+        })
+        .into_iter()
+        .collect()
+}
+
+pub(crate) fn organize_declarations(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostics: &[Diagnostic],
+) -> Option<CodeAction> {
+    let config = config::for_document(uri);
+    let organized = organize(ast, document, config.declaration_order())?;
+    let diagnostics: Vec<Diagnostic> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.range == organized.misplaced)
+        .cloned()
+        .collect();
+
+    Some(CodeAction {
+        title: "Organize declarations".into(),
+        kind: Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+        diagnostics: (!diagnostics.is_empty()).then_some(diagnostics),
+        edit: Some(mk_edit(
+            uri,
+            vec![TextEdit::new(organized.range, organized.text)],
+        )),
+        ..Default::default()
+    })
+}
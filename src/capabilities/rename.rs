@@ -12,7 +12,10 @@ use auto_lsp::{
 };
 
 use crate::{
-    ast::{ErrorName, InterfaceName, MethodName, Typedef, TypedefName, Typeref},
+    ast::{
+        EnumMemberName, ErrorName, InterfaceName, MethodName, StructFieldName, Typedef,
+        TypedefName, Typeref,
+    },
     util::{get_file_from_db, leaf_at, walk_up},
 };
 
@@ -64,6 +67,10 @@ pub fn prepare_rename(
                 Some(error_name.get_lsp_range(document).unwrap())
             } else if let Some(method_name) = walk_up::<MethodName>(ast, leaf) {
                 Some(method_name.get_lsp_range(document).unwrap())
+            } else if let Some(field_name) = walk_up::<StructFieldName>(ast, leaf) {
+                Some(field_name.get_lsp_range(document).unwrap())
+            } else if let Some(member_name) = walk_up::<EnumMemberName>(ast, leaf) {
+                Some(member_name.get_lsp_range(document).unwrap())
             } else if let Some(typeref) = walk_up::<Typeref>(ast, leaf) {
                 let document_bytes = document.as_bytes();
                 find_type(
@@ -102,6 +109,10 @@ pub fn rename(
             vec![error_name.get_lsp_range(document).unwrap()]
         } else if let Some(method_name) = walk_up::<MethodName>(ast, leaf) {
             vec![method_name.get_lsp_range(document).unwrap()]
+        } else if let Some(field_name) = walk_up::<StructFieldName>(ast, leaf) {
+            vec![field_name.get_lsp_range(document).unwrap()]
+        } else if let Some(member_name) = walk_up::<EnumMemberName>(ast, leaf) {
+            vec![member_name.get_lsp_range(document).unwrap()]
         } else if let Some(typedef_name) = walk_up::<TypedefName>(ast, leaf) {
             find_type(
                 ast,
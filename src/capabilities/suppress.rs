@@ -0,0 +1,191 @@
+use auto_lsp::{
+    core::{ast::AstNode, document::Document},
+    default::db::tracked::ParsedAst,
+    lsp_types::{CodeAction, Diagnostic, NumberOrString, Position, Range, TextEdit, Url},
+};
+
+use crate::{
+    ast::InterfaceDeclaration,
+    capabilities::{code_actions::mk_quickfix, hover::get_doc_lines},
+    util::declarations,
+};
+
+// Codes listed by a `# varlint: allow(VL0001, VL0002)` comment
+fn parse_allow(line: &str) -> Option<Vec<&str>> {
+    let (codes, _) = line
+        .trim_start()
+        .strip_prefix('#')?
+        .trim_start()
+        .strip_prefix("varlint:")?
+        .trim_start()
+        .strip_prefix("allow(")?
+        .split_once(')')?;
+
+    Some(
+        codes
+            .split(',')
+            .map(str::trim)
+            .filter(|code| !code.is_empty())
+            .collect(),
+    )
+}
+
+pub(crate) fn is_directive(line: &str) -> bool {
+    parse_allow(line).is_some()
+}
+
+struct Suppression<'a> {
+    codes: Vec<&'a str>,
+    // The whole document if `None`
+    scope: Option<Range>,
+}
+
+impl Suppression<'_> {
+    fn suppresses(&self, diagnostic: &Diagnostic) -> bool {
+        let Some(NumberOrString::String(code)) = &diagnostic.code else {
+            return false;
+        };
+
+        self.codes.contains(&code.as_str())
+            && self.scope.is_none_or(|scope| {
+                scope.start <= diagnostic.range.start && diagnostic.range.start <= scope.end
+            })
+    }
+}
+
+// Directives in the doc comment of a declaration apply to that declaration, directives in the
+// doc comment of the interface declaration or above it to the whole document
+fn suppressions<'a>(ast: &ParsedAst, document: &'a Document) -> Vec<Suppression<'a>> {
+    let text = &document.texter;
+    let mut suppressions = Vec::new();
+
+    let mut interface_line = None;
+    declarations(ast).for_each(|(node, _)| {
+        let range = node.get_lsp_range(document).unwrap();
+        let is_interface = node.is::<InterfaceDeclaration>();
+        if is_interface {
+            interface_line = Some(range.start.line);
+        }
+
+        suppressions.extend(
+            get_doc_lines(text, range.start)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(parse_allow)
+                .map(|codes| Suppression {
+                    codes,
+                    scope: (!is_interface).then_some(range),
+                }),
+        );
+    });
+
+    if let Some(interface_line) = interface_line {
+        suppressions.extend(
+            document
+                .texter
+                .text
+                .lines()
+                .take(interface_line as usize)
+                .filter_map(parse_allow)
+                .map(|codes| Suppression { codes, scope: None }),
+        );
+    }
+
+    suppressions
+}
+
+pub(crate) fn apply_suppressions(
+    ast: &ParsedAst,
+    document: &Document,
+    items: Vec<Diagnostic>,
+) -> Vec<Diagnostic> {
+    let suppressions = suppressions(ast, document);
+    items
+        .into_iter()
+        .filter(|diagnostic| {
+            !suppressions
+                .iter()
+                .any(|suppression| suppression.suppresses(diagnostic))
+        })
+        .collect()
+}
+
+// Adds the code to a directive in the doc comment of the declaration, or inserts a new one
+fn allow(document: &Document, declaration: Range, code: &str) -> Option<TextEdit> {
+    let text = &document.texter;
+    let doc = get_doc_lines(text, declaration.start).ok()?;
+    let first_line = declaration.start.line - doc.len() as u32;
+
+    if let Some((line, directive)) = doc.iter().enumerate().find(|(_, line)| is_directive(line)) {
+        let position = Position {
+            line: first_line + line as u32,
+            character: (declaration.start.character as usize + directive.find(')')?) as u32,
+        };
+        return Some(TextEdit::new(
+            Range {
+                start: position,
+                end: position,
+            },
+            format!(", {}", code),
+        ));
+    }
+
+    let indent = text
+        .get_row(declaration.start.line as usize)?
+        .get(..declaration.start.character as usize)?;
+    let position = Position {
+        line: first_line,
+        character: 0,
+    };
+    Some(TextEdit::new(
+        Range {
+            start: position,
+            end: position,
+        },
+        format!("{}# varlint: allow({})\n", indent, code),
+    ))
+}
+
+pub(crate) fn suppress(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+) -> Vec<CodeAction> {
+    let Some(NumberOrString::String(code)) = &diagnostic.code else {
+        return Vec::new();
+    };
+
+    let document_bytes = document.as_bytes();
+    let mut actions = Vec::new();
+    declarations(ast).for_each(|(node, name)| {
+        let range = node.get_lsp_range(document).unwrap();
+        if node.is::<InterfaceDeclaration>() {
+            actions.extend(allow(document, range, code).map(|edit| {
+                mk_quickfix(
+                    uri,
+                    diagnostic,
+                    format!("Suppress `{}` in file", code),
+                    vec![edit],
+                )
+            }));
+        } else if range.start <= diagnostic.range.start && diagnostic.range.start <= range.end {
+            actions.extend(allow(document, range, code).map(|edit| {
+                mk_quickfix(
+                    uri,
+                    diagnostic,
+                    format!(
+                        "Suppress `{}` for `{}`",
+                        code,
+                        name.get_text(document_bytes).unwrap()
+                    ),
+                    vec![edit],
+                )
+            }));
+        }
+    });
+
+    // The narrower suppression comes first
+    actions.reverse();
+    actions
+}
@@ -1,7 +1,9 @@
 #![allow(deprecated)]
 
-use crate::ast::{Error, InterfaceDeclaration, Method, Typedef};
-use crate::util::get_file_from_db;
+use crate::ast::{
+    EnumMemberName, Error, InterfaceDeclaration, Method, StructField, StructFieldName, Typedef,
+};
+use crate::util::{ancestors, fuzzy_match, get_file_from_db, walk_up};
 use auto_lsp::core::ast::AstNode;
 use auto_lsp::core::dispatch_once;
 use auto_lsp::core::document::Document;
@@ -148,32 +150,145 @@ pub fn document_symbols(
     Ok(Some(DocumentSymbolResponse::Nested(_symbols(db, &file))))
 }
 
+// Collects the names from the enclosing declaration down to the given node
+pub(crate) fn member_path(
+    ast: &ParsedAst,
+    document_bytes: &[u8],
+    node: &dyn AstNode,
+) -> Option<Vec<String>> {
+    let mut path = Vec::new();
+    if let Some(member) = node.downcast_ref::<EnumMemberName>() {
+        path.push(member.get_text(document_bytes).ok()?.to_string());
+    }
+
+    for ancestor in ancestors(ast, node) {
+        let name = if let Some(field) = ancestor.downcast_ref::<StructField>() {
+            field.name.cast(ast).get_text(document_bytes)
+        } else if let Some(typedef) = ancestor.downcast_ref::<Typedef>() {
+            typedef.name.cast(ast).get_text(document_bytes)
+        } else if let Some(error) = ancestor.downcast_ref::<Error>() {
+            error.name.cast(ast).get_text(document_bytes)
+        } else if let Some(method) = ancestor.downcast_ref::<Method>() {
+            method.name.cast(ast).get_text(document_bytes)
+        } else {
+            continue;
+        };
+
+        path.push(name.ok()?.to_string());
+        if !ancestor.is::<StructField>() {
+            path.reverse();
+            return Some(path);
+        }
+    }
+
+    None
+}
+
+fn _workspace_symbols(db: &impl BaseDatabase, file: &File, query: &str) -> Vec<WorkspaceSymbol> {
+    let document = file.document(db);
+    let ast = get_ast(db, *file);
+    let document_bytes = document.as_bytes();
+    let url = file.url(db);
+
+    let interface = ast
+        .iter()
+        .find_map(|node| node.lower().downcast_ref::<InterfaceDeclaration>())
+        .map(|interface| interface.name.cast(ast))
+        .filter(|name| !name.is_missing())
+        .and_then(|name| name.get_text(document_bytes).ok())
+        .unwrap_or_default();
+
+    ast.iter()
+        .filter_map(|node| {
+            let node = node.lower();
+            let (kind, range) = if node.is::<InterfaceDeclaration>() {
+                if interface.is_empty() || !fuzzy_match(query, interface) {
+                    return None;
+                }
+
+                return Some(WorkspaceSymbol {
+                    name: interface.to_string(),
+                    kind: lsp_types::SymbolKind::NAMESPACE,
+                    tags: None,
+                    container_name: None,
+                    location: OneOf::Left(Location {
+                        uri: url.to_owned(),
+                        range: node.get_lsp_range(document).unwrap(),
+                    }),
+                    data: None,
+                });
+            } else if node.is::<Error>() {
+                (
+                    lsp_types::SymbolKind::EVENT,
+                    node.get_lsp_range(document).unwrap(),
+                )
+            } else if node.is::<Method>() {
+                (
+                    lsp_types::SymbolKind::METHOD,
+                    node.get_lsp_range(document).unwrap(),
+                )
+            } else if node.is::<Typedef>() {
+                (
+                    lsp_types::SymbolKind::CLASS,
+                    node.get_lsp_range(document).unwrap(),
+                )
+            } else if node.is::<StructFieldName>() {
+                (
+                    lsp_types::SymbolKind::FIELD,
+                    walk_up::<StructField>(ast, node)?
+                        .get_lsp_range(document)
+                        .unwrap(),
+                )
+            } else if node.is::<EnumMemberName>() {
+                (
+                    lsp_types::SymbolKind::ENUM_MEMBER,
+                    node.get_lsp_range(document).unwrap(),
+                )
+            } else {
+                return None;
+            };
+
+            let mut path = member_path(ast, document_bytes, node)?;
+            let name = path.pop()?;
+            let container_name = std::iter::once(interface)
+                .chain(path.iter().map(|segment| segment.as_str()))
+                .filter(|segment| !segment.is_empty())
+                .collect::<Vec<_>>()
+                .join(".");
+            let qualified_name = if container_name.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", container_name, name)
+            };
+
+            if !fuzzy_match(query, &name) && !fuzzy_match(query, &qualified_name) {
+                return None;
+            }
+
+            Some(WorkspaceSymbol {
+                name,
+                kind,
+                tags: None,
+                container_name: (!container_name.is_empty()).then_some(container_name),
+                location: OneOf::Left(Location {
+                    uri: url.to_owned(),
+                    range,
+                }),
+                data: None,
+            })
+        })
+        .collect()
+}
+
 pub fn workspace_symbols(
     db: &impl BaseDatabase,
-    _params: WorkspaceSymbolParams,
+    params: WorkspaceSymbolParams,
 ) -> anyhow::Result<Option<WorkspaceSymbolResponse>> {
     let mut symbols = vec![];
 
     db.get_files().iter().for_each(|file| {
         let file = *file;
-        let url = file.url(db);
-
-        symbols.extend(
-            _symbols(db, &file)
-                .into_iter()
-                .map(|p| WorkspaceSymbol {
-                    name: p.name,
-                    kind: p.kind,
-                    tags: p.tags,
-                    container_name: None,
-                    location: OneOf::Left(Location {
-                        uri: url.to_owned(),
-                        range: p.range,
-                    }),
-                    data: None,
-                })
-                .collect::<Vec<_>>(),
-        );
+        symbols.extend(_workspace_symbols(db, &file, &params.query));
     });
     Ok(Some(WorkspaceSymbolResponse::Nested(symbols)))
 }
@@ -0,0 +1,136 @@
+use auto_lsp::{
+    core::{ast::AstNode, document::Document},
+    default::db::tracked::ParsedAst,
+    lsp_types::{CodeAction, CodeActionKind, Range, TextEdit, Url},
+};
+
+use crate::{
+    ast::{
+        self, Any, Array, Bool, Enum, Float, Int, Map, Maybe, Object, Struct, StructField, Typeref,
+    },
+    capabilities::code_actions::mk_edit,
+    util::{ancestors, children, most_specific_at},
+};
+
+fn is_modifier(node: &dyn AstNode) -> bool {
+    node.is::<Maybe>() || node.is::<Array>() || node.is::<Map>()
+}
+
+fn is_type(node: &dyn AstNode) -> bool {
+    is_modifier(node)
+        || node.is::<Typeref>()
+        || node.is::<Bool>()
+        || node.is::<Int>()
+        || node.is::<Float>()
+        || node.is::<ast::String>()
+        || node.is::<Object>()
+        || node.is::<Any>()
+        || node.is::<Struct>()
+        || node.is::<Enum>()
+}
+
+fn inner_type<'a>(ast: &'a ParsedAst, modifier: &'a dyn AstNode) -> Option<&'a dyn AstNode> {
+    children(ast, modifier).find(|node| is_type(*node))
+}
+
+fn mk_rewrite(uri: &Url, title: &str, edit: TextEdit) -> CodeAction {
+    CodeAction {
+        title: title.into(),
+        kind: Some(CodeActionKind::REFACTOR_REWRITE),
+        edit: Some(mk_edit(uri, vec![edit])),
+        ..Default::default()
+    }
+}
+
+// Replaces the modifier with the type it applies to, unless that would nest two `?`
+fn remove<'a>(
+    ast: &'a ParsedAst,
+    document: &Document,
+    modifier: &'a dyn AstNode,
+) -> Option<TextEdit> {
+    let inner = inner_type(ast, modifier)?;
+    let parent = modifier.get_parent(ast).map(|parent| parent.lower());
+    if inner.is::<Maybe>() && parent.is_some_and(|parent| parent.is::<Maybe>()) {
+        return None;
+    }
+
+    Some(TextEdit::new(
+        modifier.get_lsp_range(document).unwrap(),
+        inner.get_text(document.as_bytes()).unwrap().to_string(),
+    ))
+}
+
+fn prepend(document: &Document, node: &dyn AstNode, modifier: &str) -> TextEdit {
+    let start = node.get_lsp_range(document).unwrap().start;
+    TextEdit::new(Range { start, end: start }, modifier.into())
+}
+
+// Adds or removes the modifiers of the type at the given position. The type is either the
+// innermost one containing the position or the type of the field the position is on.
+pub(crate) fn type_modifiers(
+    ast: &ParsedAst,
+    document: &Document,
+    uri: &Url,
+    range: Range,
+) -> Vec<CodeAction> {
+    let Some(node) = most_specific_at(ast, document, range.start) else {
+        return Vec::new();
+    };
+    let Some(field) = ancestors(ast, node.lower()).find(|node| node.is::<StructField>()) else {
+        return Vec::new();
+    };
+    let Some(target) = ancestors(ast, node.lower())
+        .take_while(|node| !node.is::<StructField>())
+        .find(|node| is_type(*node))
+        .or_else(|| children(ast, field).find(|node| is_type(*node)))
+    else {
+        return Vec::new();
+    };
+
+    let parent = target
+        .get_parent(ast)
+        .map(|parent| parent.lower())
+        .filter(|parent| is_modifier(*parent));
+    // The modifier of the given kind that is closest to the target
+    let applied = |is: fn(&dyn AstNode) -> bool| {
+        if is(target) {
+            Some(target)
+        } else {
+            parent.filter(|parent| is(*parent))
+        }
+    };
+
+    let mut actions = Vec::new();
+    match applied(|node| node.is::<Maybe>()) {
+        Some(maybe) => actions.extend(
+            remove(ast, document, maybe).map(|edit| mk_rewrite(uri, "Make required", edit)),
+        ),
+        None => actions.push(mk_rewrite(
+            uri,
+            "Make optional",
+            prepend(document, target, "?"),
+        )),
+    }
+
+    match applied(|node| node.is::<Array>()) {
+        Some(array) => actions
+            .extend(remove(ast, document, array).map(|edit| mk_rewrite(uri, "Unwrap array", edit))),
+        None => actions.push(mk_rewrite(
+            uri,
+            "Wrap in array",
+            prepend(document, target, "[]"),
+        )),
+    }
+
+    match applied(|node| node.is::<Map>()) {
+        Some(map) => actions
+            .extend(remove(ast, document, map).map(|edit| mk_rewrite(uri, "Unwrap map", edit))),
+        None => actions.push(mk_rewrite(
+            uri,
+            "Wrap in map",
+            prepend(document, target, "[string]"),
+        )),
+    }
+
+    actions
+}
@@ -0,0 +1,156 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, RwLock};
+use std::thread;
+
+use auto_lsp::anyhow;
+use auto_lsp::lsp_server::{Connection, Message, Notification, ReqQueue, Response};
+use auto_lsp::lsp_types::notification::{self, Cancel, LogMessage, ShowMessage};
+use auto_lsp::lsp_types::request::Request;
+use auto_lsp::lsp_types::{LogMessageParams, MessageType, ShowMessageParams};
+use serde::de::DeserializeOwned;
+
+type Sender = Box<dyn Fn(Message) -> anyhow::Result<()> + Send + Sync>;
+
+// Called with the client's response to a request sent by the server
+type ResponseHandler = Box<dyn FnOnce(Response) + Send>;
+
+// Request handlers only receive the database, so messages to the client are sent globally
+static SENDER: RwLock<Option<Sender>> = RwLock::new(None);
+
+// Requests sent to the client which have not been answered yet
+static REQ_QUEUE: LazyLock<Mutex<ReqQueue<(), ResponseHandler>>> = LazyLock::new(Default::default);
+
+// The client's command to start renaming at a position, from the initialization options
+static RENAME_COMMAND: RwLock<Option<String>> = RwLock::new(None);
+
+// Number of requests cancelled by the client, so that handlers holding back a response can stop
+static CANCELLATIONS: AtomicU64 = AtomicU64::new(0);
+
+// Relays messages between the client and the returned connection, handling the responses to
+// requests sent via `send_request` on the way
+pub fn connect(client: Connection) -> Connection {
+    let (server, relay) = Connection::memory();
+    let sender = server.sender.clone();
+    *SENDER.write().unwrap() = Some(Box::new(move |message| Ok(sender.send(message)?)));
+
+    let Connection {
+        sender: to_client,
+        receiver: from_client,
+    } = client;
+    let Connection {
+        sender: to_server,
+        receiver: from_server,
+    } = relay;
+
+    thread::spawn(move || {
+        for message in from_client {
+            let message = match message {
+                Message::Response(response) => {
+                    let handler = REQ_QUEUE
+                        .lock()
+                        .unwrap()
+                        .outgoing
+                        .complete(response.id.clone());
+                    match handler {
+                        Some(handler) => {
+                            handler(response);
+                            continue;
+                        }
+                        None => Message::Response(response),
+                    }
+                }
+                Message::Notification(notification) => {
+                    if notification.method == <Cancel as notification::Notification>::METHOD {
+                        CANCELLATIONS.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Message::Notification(notification)
+                }
+                message => message,
+            };
+            if to_server.send(message).is_err() {
+                break;
+            }
+        }
+    });
+    thread::spawn(move || {
+        for message in from_server {
+            if to_client.send(message).is_err() {
+                break;
+            }
+        }
+    });
+
+    server
+}
+
+pub fn set_rename_command(command: Option<String>) {
+    *RENAME_COMMAND.write().unwrap() = command;
+}
+
+pub fn rename_command() -> Option<String> {
+    RENAME_COMMAND.read().unwrap().clone()
+}
+
+pub fn cancellations() -> u64 {
+    CANCELLATIONS.load(Ordering::SeqCst)
+}
+
+// Lets the relay finish once the server is done
+pub fn disconnect() {
+    SENDER.write().unwrap().take();
+}
+
+fn send(message: Message) -> anyhow::Result<()> {
+    match SENDER.read().unwrap().as_ref() {
+        Some(send) => send(message),
+        None => Err(anyhow::format_err!("Not connected to a client")),
+    }
+}
+
+// Sends a request to the client, passing the result to the given handler; errors are logged
+pub fn send_request<R: Request>(
+    params: R::Params,
+    on_result: impl FnOnce(R::Result) + Send + 'static,
+) -> anyhow::Result<()>
+where
+    R::Result: DeserializeOwned,
+{
+    let handler: ResponseHandler = Box::new(move |response| match response.error {
+        Some(error) => log_message(
+            MessageType::ERROR,
+            format!("{} failed: {}", R::METHOD, error.message),
+        ),
+        None => match serde_json::from_value(response.result.unwrap_or_default()) {
+            Ok(result) => on_result(result),
+            Err(err) => log_message(
+                MessageType::ERROR,
+                format!("Invalid response to {}: {}", R::METHOD, err),
+            ),
+        },
+    });
+    let request = REQ_QUEUE
+        .lock()
+        .unwrap()
+        .outgoing
+        .register(R::METHOD.into(), params, handler);
+    let id = request.id.clone();
+
+    send(request.into()).inspect_err(move |_| {
+        REQ_QUEUE.lock().unwrap().outgoing.complete(id);
+    })
+}
+
+fn send_notification<N: notification::Notification>(params: N::Params) {
+    // There is no one to tell if the client is gone
+    let _ = send(Notification::new(N::METHOD.into(), params).into());
+}
+
+// Writes to the client's log, e.g. the output panel
+pub fn log_message(typ: MessageType, message: String) {
+    send_notification::<LogMessage>(LogMessageParams { typ, message });
+}
+
+// Notifies the user
+pub fn show_message(typ: MessageType, message: String) {
+    send_notification::<ShowMessage>(ShowMessageParams { typ, message });
+}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, RwLock};
+
+use auto_lsp::lsp_types::{DiagnosticSeverity, MessageType, Url};
+use serde::Deserialize;
+
+use crate::capabilities::diagnostics::{Lint, all_lints};
+use crate::client;
+
+pub const CONFIG_FILE: &str = ".varlinklint.toml";
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeclarationKind {
+    Type,
+    Method,
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    Off,
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+// A lint is either switched on or off, or given a severity
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum LintSetting {
+    Enabled(bool),
+    Level(LintLevel),
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Config {
+    // Order of the declaration groups established by "Organize declarations"
+    #[serde(alias = "declaration-order")]
+    pub declaration_order: Option<Vec<DeclarationKind>>,
+    // Whether types used by errors count as used, as by methods
+    #[serde(alias = "errors-are-roots")]
+    pub errors_are_roots: Option<bool>,
+    // Anonymous types with fewer members are not reported when duplicated
+    #[serde(alias = "duplicate-min-size")]
+    pub duplicate_min_size: Option<usize>,
+    // Anonymous types nested deeper are reported
+    #[serde(alias = "max-nesting-depth")]
+    pub max_nesting_depth: Option<usize>,
+    // Anonymous structs with more fields are reported
+    #[serde(alias = "max-struct-fields")]
+    pub max_struct_fields: Option<usize>,
+    // Anonymous enums with more members are reported
+    #[serde(alias = "max-enum-members")]
+    pub max_enum_members: Option<usize>,
+    // Lints by name
+    pub lints: HashMap<String, LintSetting>,
+}
+
+const DEFAULT_DECLARATION_ORDER: &[DeclarationKind] = &[
+    DeclarationKind::Type,
+    DeclarationKind::Method,
+    DeclarationKind::Error,
+];
+
+impl Config {
+    pub fn declaration_order(&self) -> &[DeclarationKind] {
+        self.declaration_order
+            .as_deref()
+            .unwrap_or(DEFAULT_DECLARATION_ORDER)
+    }
+
+    pub fn errors_are_roots(&self) -> bool {
+        self.errors_are_roots.unwrap_or(true)
+    }
+
+    pub fn duplicate_min_size(&self) -> usize {
+        self.duplicate_min_size.unwrap_or(2)
+    }
+
+    pub fn max_nesting_depth(&self) -> usize {
+        self.max_nesting_depth.unwrap_or(3)
+    }
+
+    pub fn max_struct_fields(&self) -> usize {
+        self.max_struct_fields.unwrap_or(20)
+    }
+
+    pub fn max_enum_members(&self) -> usize {
+        self.max_enum_members.unwrap_or(50)
+    }
+
+    pub fn is_enabled(&self, lint: &Lint) -> bool {
+        match self.lints.get(lint.name) {
+            None => !lint.opt_in,
+            Some(LintSetting::Enabled(enabled)) => *enabled,
+            Some(LintSetting::Level(level)) => *level != LintLevel::Off,
+        }
+    }
+
+    // The configured severity, if it differs from the lint's default
+    pub fn severity(&self, lint: &Lint) -> Option<DiagnosticSeverity> {
+        match self.lints.get(lint.name)? {
+            LintSetting::Enabled(_) | LintSetting::Level(LintLevel::Off) => None,
+            LintSetting::Level(LintLevel::Error) => Some(DiagnosticSeverity::ERROR),
+            LintSetting::Level(LintLevel::Warning) => Some(DiagnosticSeverity::WARNING),
+            LintSetting::Level(LintLevel::Information) => Some(DiagnosticSeverity::INFORMATION),
+            LintSetting::Level(LintLevel::Hint) => Some(DiagnosticSeverity::HINT),
+        }
+    }
+
+    // Settings of `other` take precedence
+    fn merge(&mut self, other: &Config) {
+        if other.declaration_order.is_some() {
+            self.declaration_order = other.declaration_order.clone();
+        }
+        if other.errors_are_roots.is_some() {
+            self.errors_are_roots = other.errors_are_roots;
+        }
+        if other.duplicate_min_size.is_some() {
+            self.duplicate_min_size = other.duplicate_min_size;
+        }
+        if other.max_nesting_depth.is_some() {
+            self.max_nesting_depth = other.max_nesting_depth;
+        }
+        if other.max_struct_fields.is_some() {
+            self.max_struct_fields = other.max_struct_fields;
+        }
+        if other.max_enum_members.is_some() {
+            self.max_enum_members = other.max_enum_members;
+        }
+        self.lints.extend(
+            other
+                .lints
+                .iter()
+                .map(|(name, setting)| (name.clone(), *setting)),
+        );
+    }
+}
+
+#[derive(Default)]
+struct Overrides {
+    initialization_options: Config,
+    settings: Config,
+}
+
+// Request handlers only receive the database, so the configuration is kept globally
+static OVERRIDES: LazyLock<RwLock<Overrides>> = LazyLock::new(Default::default);
+
+// Parsed configuration files, `None` if the file is invalid
+static FILES: LazyLock<RwLock<HashMap<PathBuf, Option<Config>>>> = LazyLock::new(Default::default);
+
+// Incremented whenever the configuration may have changed
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+pub fn is_config_file(uri: &Url) -> bool {
+    uri.to_file_path()
+        .ok()
+        .is_some_and(|path| path.file_name().is_some_and(|name| name == CONFIG_FILE))
+}
+
+// Settings for lints which do not exist, e.g. because of a typo, have no effect
+fn warn_unknown_lints(config: &Config, source: &str) {
+    let mut unknown: Vec<&String> = config
+        .lints
+        .keys()
+        .filter(|name| !all_lints().any(|lint| lint.name == name.as_str()))
+        .collect();
+    unknown.sort();
+    for name in unknown {
+        client::log_message(
+            MessageType::WARNING,
+            format!("Unknown lint `{}` in {}", name, source),
+        );
+    }
+}
+
+fn read_file(path: &Path) -> Option<Config> {
+    if let Some(config) = FILES.read().unwrap().get(path) {
+        return config.clone();
+    }
+
+    let config = fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| toml::from_str(&text).map_err(|err| err.to_string()))
+        .inspect(|config| warn_unknown_lints(config, &path.display().to_string()))
+        .inspect_err(|err| {
+            client::log_message(
+                MessageType::ERROR,
+                format!("Invalid configuration {}: {}", path.display(), err),
+            )
+        })
+        .ok();
+    FILES
+        .write()
+        .unwrap()
+        .insert(path.to_path_buf(), config.clone());
+    config
+}
+
+// The nearest configuration file in the directory of the document or above
+fn find_file(uri: &Url) -> Option<PathBuf> {
+    let path = uri.to_file_path().ok()?;
+    path.ancestors()
+        .skip(1)
+        .map(|dir| dir.join(CONFIG_FILE))
+        .find(|file| file.is_file())
+}
+
+pub fn for_document(uri: &Url) -> Config {
+    let mut config = find_file(uri)
+        .and_then(|path| read_file(&path))
+        .unwrap_or_default();
+
+    let overrides = OVERRIDES.read().unwrap();
+    config.merge(&overrides.initialization_options);
+    config.merge(&overrides.settings);
+    config
+}
+
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::SeqCst)
+}
+
+pub fn set_initialization_options(config: Config) {
+    warn_unknown_lints(&config, "initialization options");
+    OVERRIDES.write().unwrap().initialization_options = config;
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+// Settings sent via `workspace/didChangeConfiguration` replace the previous ones
+pub fn set_settings(config: Config) {
+    warn_unknown_lints(&config, "settings");
+    OVERRIDES.write().unwrap().settings = config;
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+// Configuration files are read again when next needed
+pub fn invalidate_files() {
+    FILES.write().unwrap().clear();
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
@@ -1,3 +1,7 @@
 pub mod ast;
 pub mod util;
 pub mod capabilities;
+pub mod workspace;
+pub mod push_diagnostics;
+pub mod config;
+pub mod client;
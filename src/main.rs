@@ -5,18 +5,26 @@ use auto_lsp::default::server::file_events::{
     change_text_document, changed_watched_files, open_text_document,
 };
 use auto_lsp::lsp_server::{self, Connection};
-use auto_lsp::lsp_types::ServerCapabilities;
 use auto_lsp::lsp_types::notification::{
-    Cancel, DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument,
-    DidOpenTextDocument, DidSaveTextDocument, SetTrace,
+    Cancel, DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles,
+    DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument, SetTrace,
 };
 use auto_lsp::lsp_types::request::{
-    Completion, DocumentDiagnosticRequest, DocumentHighlightRequest, DocumentSymbolRequest,
-    FoldingRangeRequest, Formatting, GotoDefinition, HoverRequest, PrepareRenameRequest,
-    References, Rename, SelectionRangeRequest, SemanticTokensFullRequest,
+    CodeActionRequest, Completion, DocumentDiagnosticRequest, DocumentHighlightRequest,
+    DocumentSymbolRequest, ExecuteCommand, FoldingRangeRequest, Formatting, GotoDefinition,
+    HoverRequest, PrepareRenameRequest, References, RegisterCapability, Rename,
+    SelectionRangeRequest, SemanticTokensFullRequest, WorkspaceDiagnosticRefresh,
+    WorkspaceDiagnosticRequest, WorkspaceSymbolRequest,
 };
 use auto_lsp::lsp_types::{self, HoverProviderCapability, OneOf};
-use auto_lsp::lsp_types::{DiagnosticOptions, DiagnosticServerCapabilities};
+use auto_lsp::lsp_types::{
+    CodeActionKind, CodeActionOptions, CodeActionProviderCapability, DiagnosticOptions,
+    DiagnosticServerCapabilities, DidChangeTextDocumentParams, DidChangeWatchedFilesParams,
+    DidChangeWatchedFilesRegistrationOptions, DidOpenTextDocumentParams, ExecuteCommandOptions,
+    FileChangeType, FileEvent, FileSystemWatcher, GlobPattern, InitializeParams, MessageType,
+    Registration, RegistrationParams, ServerCapabilities, TextDocumentContentChangeEvent, Url,
+    VersionedTextDocumentIdentifier,
+};
 use auto_lsp::server::Session;
 use auto_lsp::server::notification_registry::NotificationRegistry;
 use auto_lsp::server::options::InitOptions;
@@ -25,20 +33,29 @@ use auto_lsp::server::vendored::intent::ThreadIntent;
 use lsp_types::ServerInfo;
 use std::error::Error;
 use std::panic::RefUnwindSafe;
+use varlink_language_server::capabilities::code_actions::code_actions;
 use varlink_language_server::capabilities::completion::completion;
-use varlink_language_server::capabilities::diagnostics::diagnostics;
+use varlink_language_server::capabilities::diagnostics::{diagnostics, workspace_diagnostics};
 use varlink_language_server::capabilities::folding_range::folding_range;
 use varlink_language_server::capabilities::formatting::formatting;
 use varlink_language_server::capabilities::goto_definition::goto_definition;
 use varlink_language_server::capabilities::highlight::highlight;
 use varlink_language_server::capabilities::hover::hover;
+use varlink_language_server::capabilities::infer_type::{INFER_TYPE_COMMAND, execute_command};
 use varlink_language_server::capabilities::references::references;
 use varlink_language_server::capabilities::rename::{prepare_rename, rename};
 use varlink_language_server::capabilities::selection_range::selection_range;
 use varlink_language_server::capabilities::semantic_tokens::{
     SUPPORTED_TYPES, semantic_tokens_full,
 };
-use varlink_language_server::capabilities::symbols::document_symbols;
+use varlink_language_server::capabilities::symbols::{document_symbols, workspace_symbols};
+use varlink_language_server::client;
+use varlink_language_server::config::{self, CONFIG_FILE, Config};
+use varlink_language_server::push_diagnostics::{
+    clear, publish, publish_debounced, republish, supports_diagnostic_refresh,
+    supports_pull_diagnostics,
+};
+use varlink_language_server::workspace::{GLOB_PATTERN, read_document, workspace_documents};
 
 use varlink_language_server::ast::Interface;
 
@@ -48,8 +65,96 @@ auto_lsp::configure_parser!(
     ast_root: Interface
 );
 
+fn index_workspace(session: &mut Session<BaseDb>, params: &InitializeParams) {
+    workspace_documents(params)
+        .into_iter()
+        .for_each(|document| {
+            if let Err(err) = document.and_then(|text_document| {
+                Ok(open_text_document(
+                    session,
+                    DidOpenTextDocumentParams { text_document },
+                    &*VARLINK,
+                )?)
+            }) {
+                client::log_message(MessageType::ERROR, err.to_string());
+            }
+        });
+}
+
+// Replaces the content of a document that is already in the database
+fn replace_text_document<Db: BaseDatabase>(
+    session: &mut Session<Db>,
+    uri: Url,
+    version: i32,
+    text: String,
+) -> anyhow::Result<()> {
+    Ok(change_text_document(
+        session,
+        DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier { uri, version },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text,
+            }],
+        },
+    )?)
+}
+
+fn register_file_watchers(params: &InitializeParams) -> anyhow::Result<()> {
+    let supported = params
+        .capabilities
+        .workspace
+        .as_ref()
+        .and_then(|workspace| workspace.did_change_watched_files.as_ref())
+        .and_then(|capability| capability.dynamic_registration)
+        .unwrap_or(false);
+
+    if !supported {
+        return Ok(());
+    }
+
+    let registration = RegistrationParams {
+        registrations: vec![Registration {
+            id: "varlink/didChangeWatchedFiles".into(),
+            method: "workspace/didChangeWatchedFiles".into(),
+            register_options: Some(serde_json::to_value(
+                DidChangeWatchedFilesRegistrationOptions {
+                    watchers: vec![
+                        FileSystemWatcher {
+                            glob_pattern: GlobPattern::String(GLOB_PATTERN.into()),
+                            kind: None,
+                        },
+                        FileSystemWatcher {
+                            glob_pattern: GlobPattern::String(format!("**/{}", CONFIG_FILE)),
+                            kind: None,
+                        },
+                    ],
+                },
+            )?),
+        }],
+    };
+
+    client::send_request::<RegisterCapability>(registration, |()| {})
+}
+
+// Lets the client know that diagnostics may have changed with the configuration
+fn refresh_diagnostics<Db: BaseDatabase>(
+    session: &Session<Db>,
+    push_diagnostics: bool,
+    refresh_support: bool,
+) -> anyhow::Result<()> {
+    if push_diagnostics {
+        republish(session)
+    } else if refresh_support {
+        client::send_request::<WorkspaceDiagnosticRefresh>((), |()| {})
+    } else {
+        Ok(())
+    }
+}
+
 fn main_loop(connection: Connection, db: BaseDb) -> anyhow::Result<()> {
-    let (session, _params) = Session::create(
+    let (mut session, params) = Session::create(
         InitOptions {
             server_info: Some(ServerInfo {
                 name: "varlink-language-server".into(),
@@ -59,6 +164,7 @@ fn main_loop(connection: Connection, db: BaseDb) -> anyhow::Result<()> {
                 text_document_sync: TEXT_DOCUMENT_SYNC.clone(),
                 diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
                     DiagnosticOptions {
+                        workspace_diagnostics: true,
                         ..Default::default()
                     },
                 )),
@@ -68,6 +174,7 @@ fn main_loop(connection: Connection, db: BaseDb) -> anyhow::Result<()> {
                     None,
                 ),
                 document_symbol_provider: Some(OneOf::Left(true)),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
                 definition_provider: Some(crate::OneOf::Left(true)),
                 references_provider: Some(lsp_types::OneOf::Left(true)),
                 document_highlight_provider: Some(OneOf::Left(true)),
@@ -86,6 +193,23 @@ fn main_loop(connection: Connection, db: BaseDb) -> anyhow::Result<()> {
                 completion_provider: Some(lsp_types::CompletionOptions {
                     ..Default::default()
                 }),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![
+                            CodeActionKind::QUICKFIX,
+                            CodeActionKind::REFACTOR_EXTRACT,
+                            CodeActionKind::REFACTOR_INLINE,
+                            CodeActionKind::REFACTOR_REWRITE,
+                            CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+                            CodeActionKind::SOURCE_FIX_ALL,
+                        ]),
+                        ..Default::default()
+                    },
+                )),
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: vec![INFER_TYPE_COMMAND.into()],
+                    ..Default::default()
+                }),
                 ..Default::default()
             },
         },
@@ -93,25 +217,51 @@ fn main_loop(connection: Connection, db: BaseDb) -> anyhow::Result<()> {
         db,
     )?;
 
+    if let Some(options) = &params.initialization_options {
+        client::set_rename_command(
+            options
+                .get("renameCommand")
+                .and_then(|command| command.as_str())
+                .map(Into::into),
+        );
+        match serde_json::from_value(options.clone()) {
+            Ok(options) => config::set_initialization_options(options),
+            Err(err) => client::log_message(
+                MessageType::ERROR,
+                format!("Invalid initialization options: {}", err),
+            ),
+        }
+    }
+
+    index_workspace(&mut session, &params);
+    register_file_watchers(&params)?;
+
     let mut request_registry = RequestRegistry::<BaseDb>::default();
     let mut notification_registry = NotificationRegistry::<BaseDb>::default();
 
     session.main_loop(
         on_requests(&mut request_registry),
-        on_notifications(&mut notification_registry),
+        on_notifications(
+            &mut notification_registry,
+            !supports_pull_diagnostics(&params),
+            supports_diagnostic_refresh(&params),
+        ),
     )
 }
 
 fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
     let (connection, io_threads) = Connection::stdio();
     let db = BaseDb::default();
-    main_loop(connection, db)?;
+    main_loop(client::connect(connection), db)?;
+    client::disconnect();
     io_threads.join()?;
     Ok(())
 }
 
-fn on_notifications<Db: BaseDatabase + Clone + RefUnwindSafe>(
+fn on_notifications<Db: BaseDatabase + Clone + RefUnwindSafe + Send + 'static>(
     registry: &mut NotificationRegistry<Db>,
+    push_diagnostics: bool,
+    refresh_support: bool,
 ) -> &mut NotificationRegistry<Db> {
     registry
         .on_mut::<Cancel, _>(|s, p| {
@@ -126,13 +276,100 @@ fn on_notifications<Db: BaseDatabase + Clone + RefUnwindSafe>(
 
             Ok(())
         })
-        .on_mut::<DidChangeTextDocument, _>(|s, p| Ok(change_text_document(s, p)?))
-        .on_mut::<DidChangeWatchedFiles, _>(|s, p| {
-            Ok(changed_watched_files(s, p, |_| Some(&*VARLINK))?)
+        .on_mut::<DidChangeConfiguration, _>(move |s, p| {
+            // Settings may be namespaced by the client
+            let settings = p.settings.get("varlink").unwrap_or(&p.settings);
+            if settings.is_null() {
+                config::set_settings(Config::default());
+            } else {
+                match serde_json::from_value(settings.clone()) {
+                    Ok(settings) => config::set_settings(settings),
+                    Err(err) => client::log_message(
+                        MessageType::ERROR,
+                        format!("Invalid settings: {}", err),
+                    ),
+                }
+            }
+
+            refresh_diagnostics(s, push_diagnostics, refresh_support)
+        })
+        .on_mut::<DidChangeTextDocument, _>(move |s, p| {
+            let uri = p.text_document.uri.clone();
+            change_text_document(s, p)?;
+            if push_diagnostics {
+                publish_debounced(s, uri);
+            }
+
+            Ok(())
+        })
+        .on_mut::<DidChangeWatchedFiles, _>(move |s, mut p| {
+            let (config_files, changes) = p
+                .changes
+                .into_iter()
+                .partition::<Vec<_>, _>(|change| config::is_config_file(&change.uri));
+            p.changes = changes;
+            changed_watched_files(s, p, |_| Some(&*VARLINK))?;
+
+            if !config_files.is_empty() {
+                config::invalidate_files();
+                refresh_diagnostics(s, push_diagnostics, refresh_support)?;
+            }
+
+            Ok(())
+        })
+        .on_mut::<DidOpenTextDocument, _>(move |s, p| {
+            let uri = p.text_document.uri.clone();
+            // Indexed files were read from disk, but the editor's buffer may differ
+            if s.db.get_file(&uri).is_some() {
+                replace_text_document(
+                    s,
+                    uri.clone(),
+                    p.text_document.version,
+                    p.text_document.text,
+                )?;
+            } else {
+                open_text_document(s, p, &*VARLINK)?;
+            }
+            if push_diagnostics {
+                publish(s, &uri)?;
+            }
+
+            Ok(())
+        })
+        .on_mut::<DidCloseTextDocument, _>(move |s, p| {
+            let uri = p.text_document.uri;
+            if push_diagnostics {
+                clear(s, uri.clone())?;
+            }
+
+            // Unsaved changes are discarded, so fall back to the content on disk
+            let Some(document) = uri
+                .to_file_path()
+                .ok()
+                .and_then(|path| read_document(&path).ok())
+            else {
+                // Without a file on disk, the document is gone once closed
+                return Ok(changed_watched_files(
+                    s,
+                    DidChangeWatchedFilesParams {
+                        changes: vec![FileEvent {
+                            uri,
+                            typ: FileChangeType::DELETED,
+                        }],
+                    },
+                    |_| Some(&*VARLINK),
+                )?);
+            };
+
+            replace_text_document(s, uri, document.version, document.text)
+        })
+        .on_mut::<DidSaveTextDocument, _>(move |s, p| {
+            if push_diagnostics {
+                publish(s, &p.text_document.uri)?;
+            }
+
+            Ok(())
         })
-        .on_mut::<DidOpenTextDocument, _>(|s, p| Ok(open_text_document(s, p, &*VARLINK)?))
-        .on::<DidCloseTextDocument, _>(ThreadIntent::Worker, |_s, _p| Ok(()))
-        .on::<DidSaveTextDocument, _>(ThreadIntent::Worker, |_s, _p| Ok(())) // TODO
         .on::<SetTrace, _>(ThreadIntent::Worker, |_s, _p| Ok(()))
 }
 
@@ -140,9 +377,11 @@ fn on_requests<Db: BaseDatabase + Clone + RefUnwindSafe>(
     registry: &mut RequestRegistry<Db>,
 ) -> &mut RequestRegistry<Db> {
     registry
+        .on::<CodeActionRequest, _>(ThreadIntent::Worker, code_actions)
         .on::<Completion, _>(ThreadIntent::Worker, completion)
         .on::<DocumentDiagnosticRequest, _>(ThreadIntent::Worker, diagnostics)
         .on::<DocumentSymbolRequest, _>(ThreadIntent::Worker, document_symbols)
+        .on::<ExecuteCommand, _>(ThreadIntent::Worker, execute_command)
         .on::<FoldingRangeRequest, _>(ThreadIntent::Worker, folding_range)
         .on::<Formatting, _>(ThreadIntent::Worker, formatting)
         .on::<DocumentHighlightRequest, _>(ThreadIntent::Worker, highlight)
@@ -153,4 +392,6 @@ fn on_requests<Db: BaseDatabase + Clone + RefUnwindSafe>(
         .on::<Rename, _>(ThreadIntent::Worker, rename)
         .on::<SelectionRangeRequest, _>(ThreadIntent::Worker, selection_range)
         .on::<SemanticTokensFullRequest, _>(ThreadIntent::Worker, semantic_tokens_full)
+        .on::<WorkspaceDiagnosticRequest, _>(ThreadIntent::Worker, workspace_diagnostics)
+        .on::<WorkspaceSymbolRequest, _>(ThreadIntent::Worker, workspace_symbols)
 }
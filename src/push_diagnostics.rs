@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+use std::panic::{AssertUnwindSafe, RefUnwindSafe};
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use auto_lsp::anyhow;
+use auto_lsp::default::db::BaseDatabase;
+use auto_lsp::lsp_server::{Message, Notification};
+use auto_lsp::lsp_types::notification::{self, PublishDiagnostics};
+use auto_lsp::lsp_types::{InitializeParams, PublishDiagnosticsParams, Url};
+use auto_lsp::salsa::{Cancelled, Database};
+use auto_lsp::server::Session;
+
+use crate::capabilities::diagnostics::publish_diagnostics;
+
+const DEBOUNCE: Duration = Duration::from_millis(300);
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// Documents whose latest revision has not been published yet
+static PENDING: LazyLock<Mutex<HashSet<Url>>> = LazyLock::new(Default::default);
+
+// Documents for which diagnostics have been published and not yet cleared
+static PUBLISHED: LazyLock<Mutex<HashSet<Url>>> = LazyLock::new(Default::default);
+
+pub fn supports_pull_diagnostics(params: &InitializeParams) -> bool {
+    params
+        .capabilities
+        .text_document
+        .as_ref()
+        .and_then(|text_document| text_document.diagnostic.as_ref())
+        .is_some()
+}
+
+pub fn supports_diagnostic_refresh(params: &InitializeParams) -> bool {
+    params
+        .capabilities
+        .workspace
+        .as_ref()
+        .and_then(|workspace| workspace.diagnostic.as_ref())
+        .and_then(|diagnostic| diagnostic.refresh_support)
+        .unwrap_or(false)
+}
+
+fn mk_notification(params: PublishDiagnosticsParams) -> Message {
+    Notification::new(
+        <PublishDiagnostics as notification::Notification>::METHOD.into(),
+        params,
+    )
+    .into()
+}
+
+pub fn publish<Db: BaseDatabase>(session: &Session<Db>, uri: &Url) -> anyhow::Result<()> {
+    PENDING.lock().unwrap().remove(uri);
+    PUBLISHED.lock().unwrap().insert(uri.clone());
+    session
+        .connection
+        .sender
+        .send(mk_notification(publish_diagnostics(&session.db, uri)?))?;
+    Ok(())
+}
+
+pub fn clear<Db: BaseDatabase>(session: &Session<Db>, uri: Url) -> anyhow::Result<()> {
+    PENDING.lock().unwrap().remove(&uri);
+    PUBLISHED.lock().unwrap().remove(&uri);
+    session
+        .connection
+        .sender
+        .send(mk_notification(PublishDiagnosticsParams {
+            uri,
+            diagnostics: vec![],
+            version: None,
+        }))?;
+    Ok(())
+}
+
+// Publishes again, e.g. after the configuration changed
+pub fn republish<Db: BaseDatabase>(session: &Session<Db>) -> anyhow::Result<()> {
+    let published: Vec<Url> = PUBLISHED.lock().unwrap().iter().cloned().collect();
+    published.iter().try_for_each(|uri| publish(session, uri))
+}
+
+// Publishes once no further change arrived for a while. Any change to the database cancels
+// the snapshot held by the waiting thread, in which case the documents remain pending and are
+// published by the thread scheduled for that change.
+pub fn publish_debounced<Db: BaseDatabase + Clone + RefUnwindSafe + Send + 'static>(
+    session: &Session<Db>,
+    uri: Url,
+) {
+    PENDING.lock().unwrap().insert(uri);
+
+    let db = session.db.clone();
+    let sender = session.connection.sender.clone();
+    thread::spawn(move || {
+        let _ = Cancelled::catch(AssertUnwindSafe(|| {
+            let deadline = Instant::now() + DEBOUNCE;
+            while Instant::now() < deadline {
+                db.unwind_if_revision_cancelled();
+                thread::sleep(POLL_INTERVAL);
+            }
+
+            let pending: Vec<Url> = PENDING.lock().unwrap().iter().cloned().collect();
+            for uri in pending {
+                if let Ok(params) = publish_diagnostics(&db, &uri) {
+                    let _ = sender.send(mk_notification(params));
+                }
+                PENDING.lock().unwrap().remove(&uri);
+            }
+        }));
+    });
+}
@@ -1,3 +1,5 @@
+use std::collections::{HashMap, HashSet};
+
 use auto_lsp::{
     anyhow,
     core::{ast::AstNode, document::Document},
@@ -5,7 +7,10 @@ use auto_lsp::{
     lsp_types::{Position, SemanticTokenType, Url},
 };
 
-use crate::capabilities::semantic_tokens::SUPPORTED_TYPES;
+use crate::{
+    ast::{Error, InterfaceDeclaration, Method, Typedef, Typeref},
+    capabilities::semantic_tokens::SUPPORTED_TYPES,
+};
 
 // It would be great to have some of these upstream
 
@@ -18,6 +23,13 @@ pub fn get_token_index(type_: SemanticTokenType) -> u32 {
     SUPPORTED_TYPES.iter().position(|x| *x == type_).unwrap() as u32
 }
 
+pub fn end_of_document(document: &Document) -> Position {
+    Position {
+        line: usize::from(document.texter.br_indexes.row_count()) as u32,
+        character: 0,
+    }
+}
+
 // Note that the given nodes are assumed to be ordered by their starting position
 
 // Finds the rightmost leaf at or before the given position
@@ -92,3 +104,178 @@ pub fn capture_at<'a, T: AstNode>(
 ) -> Option<&'a T> {
     most_specific_at(ast, document, pos).and_then(|n| walk_up::<T>(ast, n.lower()))
 }
+
+// Iterates over the given node and all nodes above it
+pub fn ancestors<'a>(
+    ast: &'a ParsedAst,
+    node: &'a dyn AstNode,
+) -> impl Iterator<Item = &'a dyn AstNode> {
+    std::iter::successors(Some(node), move |n| n.get_parent(ast).map(|p| p.lower()))
+}
+
+// Top-level declarations together with their names
+pub fn declarations<'a>(
+    ast: &'a ParsedAst,
+) -> impl Iterator<Item = (&'a dyn AstNode, &'a dyn AstNode)> {
+    ast.iter().filter_map(|node| {
+        let node = node.lower();
+        let name: &dyn AstNode =
+            if let Some(interface) = node.downcast_ref::<InterfaceDeclaration>() {
+                interface.name.cast(ast)
+            } else if let Some(typedef) = node.downcast_ref::<Typedef>() {
+                typedef.name.cast(ast)
+            } else if let Some(method) = node.downcast_ref::<Method>() {
+                method.name.cast(ast)
+            } else if let Some(error) = node.downcast_ref::<Error>() {
+                error.name.cast(ast)
+            } else {
+                return None;
+            };
+        Some((node, name))
+    })
+}
+
+// The spaces and tabs at the start of a row
+pub fn indentation(row: &str) -> &str {
+    &row[..row.len() - row.trim_start_matches([' ', '\t']).len()]
+}
+
+// Finds the nodes whose parent is the given node
+pub fn children<'a>(
+    ast: &'a ParsedAst,
+    node: &'a dyn AstNode,
+) -> impl Iterator<Item = &'a dyn AstNode> {
+    ast.iter().map(|n| n.lower()).filter(move |n| {
+        n.get_parent(ast)
+            .is_some_and(|p| std::ptr::addr_eq(p.lower(), node))
+    })
+}
+
+// Case-insensitive subsequence matching as usually done for symbol search
+pub fn fuzzy_match(query: &str, candidate: &str) -> bool {
+    let mut candidate = candidate.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| candidate.any(|c| c == q))
+}
+
+// Levenshtein distance between two strings
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+// Selects the candidates that are likely meant instead of the given name, closest first
+pub fn similar_names<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    const MAX_SUGGESTIONS: usize = 3;
+
+    let name = name.to_lowercase();
+    let threshold = (name.chars().count() / 3).max(1);
+    let mut similar: Vec<(usize, &str)> = candidates
+        .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .collect();
+
+    similar.sort();
+    similar.dedup();
+    similar
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+// Converts snake_case or camelCase names to PascalCase
+pub fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+// Maps the name of each type definition to the typerefs within it
+pub fn type_dependencies<'a>(
+    ast: &'a ParsedAst,
+    document_bytes: &'a [u8],
+) -> HashMap<&'a str, Vec<&'a Typeref>> {
+    let mut result = HashMap::new();
+    ast.iter().for_each(|node| {
+        if let Some(typeref) = node.lower().downcast_ref::<Typeref>()
+            && let Some(typedef) = walk_up::<Typedef>(ast, typeref)
+        {
+            result
+                .entry(typedef.name.cast(ast).get_text(document_bytes).unwrap())
+                .or_insert_with(Vec::new)
+                .push(typeref);
+        }
+    });
+
+    result
+}
+
+// Whether the definition of the given type refers back to it, possibly through other types
+pub fn is_recursive(
+    dependencies: &HashMap<&str, Vec<&Typeref>>,
+    document_bytes: &[u8],
+    name: &str,
+) -> bool {
+    let mut visited = HashSet::new();
+    let mut stack = vec![name];
+    while let Some(current) = stack.pop() {
+        for typeref in dependencies.get(current).into_iter().flatten() {
+            let next = typeref.get_text(document_bytes).unwrap();
+            if next == name {
+                return true;
+            }
+
+            if visited.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+
+    false
+}
+
+// Converts PascalCase, camelCase or kebab-case names to snake_case
+pub fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut after_lowercase = false;
+    for c in name.chars() {
+        if c == '_' || c == '-' {
+            if !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+            after_lowercase = false;
+        } else if c.is_uppercase() {
+            if after_lowercase {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+            after_lowercase = false;
+        } else {
+            result.push(c);
+            after_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        }
+    }
+
+    result
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use auto_lsp::anyhow;
+use auto_lsp::lsp_types::{InitializeParams, TextDocumentItem, Url};
+
+pub const LANGUAGE_ID: &str = "varlink";
+pub const EXTENSION: &str = "varlink";
+pub const GLOB_PATTERN: &str = "**/*.varlink";
+
+fn is_varlink_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == EXTENSION)
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        let path = entry.path();
+        if file_type.is_dir() {
+            // Skip hidden directories such as `.git`
+            if !entry.file_name().to_string_lossy().starts_with('.') {
+                collect_files(&path, files);
+            }
+        } else if file_type.is_file() && is_varlink_file(&path) {
+            files.push(path);
+        }
+    }
+}
+
+pub fn workspace_roots(params: &InitializeParams) -> Vec<PathBuf> {
+    match &params.workspace_folders {
+        Some(folders) => folders
+            .iter()
+            .filter_map(|folder| folder.uri.to_file_path().ok())
+            .collect(),
+        #[allow(deprecated)]
+        None => params
+            .root_uri
+            .iter()
+            .filter_map(|uri| uri.to_file_path().ok())
+            .collect(),
+    }
+}
+
+pub fn read_document(path: &Path) -> anyhow::Result<TextDocumentItem> {
+    let uri = Url::from_file_path(path)
+        .map_err(|_| anyhow::format_err!("Invalid path: {}", path.display()))?;
+    let text = fs::read_to_string(path)
+        .map_err(|err| anyhow::format_err!("Cannot read {}: {}", path.display(), err))?;
+
+    Ok(TextDocumentItem {
+        uri,
+        language_id: LANGUAGE_ID.into(),
+        version: 0,
+        text,
+    })
+}
+
+// Reads all Varlink files below the workspace folders
+pub fn workspace_documents(params: &InitializeParams) -> Vec<anyhow::Result<TextDocumentItem>> {
+    let mut files = Vec::new();
+    workspace_roots(params)
+        .iter()
+        .for_each(|root| collect_files(root, &mut files));
+
+    files.sort();
+    files.dedup();
+    files.iter().map(|path| read_document(path)).collect()
+}